@@ -0,0 +1,36 @@
+use std::path::Path;
+use criterion::{ criterion_group, criterion_main, Criterion };
+use advent_of_code_2019::days::{ self, Stars };
+
+/// These parts take minutes each, so they're only benchmarked if
+/// `AOC_BENCH_SLOW` is set.
+const SLOW_PARTS: &[(usize,usize)] = &[(16, 2), (18, 2)];
+
+/// Time each part of each day against its input in the inputs folder.
+fn days(c: &mut Criterion) {
+    let include_slow = std::env::var_os("AOC_BENCH_SLOW").is_some();
+    let mut group = c.benchmark_group("days");
+    group.sample_size(10);
+    for day in days::all() {
+        for part in 1..=2 {
+            if !include_slow && SLOW_PARTS.contains(&(day.number(), part)) {
+                continue
+            }
+            let run = || {
+                let mut stars = Stars::new(|_| {});
+                stars.only(part);
+                day.run_default(Path::new("inputs"), &mut stars).expect("day should run");
+                stars.stars().len()
+            };
+            // Some days only have one part:
+            if run() == 0 {
+                continue
+            }
+            group.bench_function(format!("{}/part{}", day.name(), part), |b| b.iter(run));
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
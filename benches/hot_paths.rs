@@ -0,0 +1,55 @@
+use std::str::FromStr;
+use criterion::{ criterion_group, criterion_main, Criterion };
+use advent_of_code_2019::days::{ day16, day18 };
+use advent_of_code_2019::days::day24::bugs::Bugs;
+use advent_of_code_2019::days::day24::recursive_bugs::RecursiveBugs;
+use advent_of_code_2019::support::{ Intcode, Outcome };
+
+fn input(day: usize) -> String {
+    std::fs::read_to_string(format!("inputs/day{:02}.txt", day)).expect("input should exist")
+}
+
+/// Step through day 9's BOOST program in sensor boost mode,
+/// which runs a few hundred thousand instructions.
+fn intcode_step(c: &mut Criterion) {
+    let machine = Intcode::from_str(&input(9)).unwrap();
+    c.bench_function("intcode/step", |b| b.iter(|| {
+        let mut machine = machine.clone();
+        let mut outputs = 0;
+        while let Some(outcome) = machine.step().unwrap() {
+            match outcome {
+                Outcome::NeedsInput(provider) => machine.provide_input(provider.value(2)).unwrap(),
+                Outcome::Output(_) => outputs += 1
+            }
+        }
+        outputs
+    }));
+}
+
+fn day16_phase(c: &mut Criterion) {
+    let signal = day16::parse_input(&input(16)).unwrap();
+    c.bench_function("day16/phase", |b| b.iter(|| day16::phase(&signal)));
+}
+
+/// Part 1 is dominated by the search for the shortest route
+/// that collects every key.
+fn day18_search(c: &mut Criterion) {
+    let input = input(18);
+    c.bench_function("day18/search", |b| b.iter(|| day18::part1(&input).unwrap()));
+}
+
+fn day24_step(c: &mut Criterion) {
+    let input = input(24);
+    let bugs = Bugs::from_str(&input).unwrap();
+    c.bench_function("day24/step", |b| b.iter(|| bugs.step()));
+
+    // Let the bugs spread over a few levels first:
+    let mut recursive = RecursiveBugs::from_str(&input).unwrap();
+    for _ in 0..100 {
+        recursive = recursive.step();
+    }
+    c.bench_function("day24/recursive_step", |b| b.iter(|| recursive.step()));
+}
+
+criterion_group!(benches, intcode_step, day16_phase, day18_search, day24_step);
+criterion_main!(benches);
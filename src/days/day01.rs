@@ -1,28 +1,37 @@
 use crate::error::Error;
+use crate::days::{ Solution, Stars, NoArgs };
+use crate::support::Answer;
+use crate::support::parse::lines;
 
-pub fn part1(input: &str) -> Result<(),Error> {
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 1;
+    const TITLE: &'static str = "The Tyranny of the Rocket Equation";
+    type Args = NoArgs;
+    fn solve(input: &str, _: NoArgs, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<Answer,Error> {
     let masses = parse_masses(input)?;
     let sum_fuel_reqs: i64 = masses.into_iter().map(fuel_req).sum();
-    println!("Star 1: {}", sum_fuel_reqs);
-    Ok(())
+    Ok(sum_fuel_reqs.into())
 }
 
-pub fn part2(input: &str) -> Result<(),Error> {
+pub fn part2(input: &str) -> Result<Answer,Error> {
     let masses = parse_masses(input)?;
     let sum_fuel_reqs: i64 = masses.into_iter().map(recursive_fuel_req).sum();
-    println!("Star 2: {}", sum_fuel_reqs);
-    Ok(())
+    Ok(sum_fuel_reqs.into())
 }
 
 fn parse_masses(input: &str) -> Result<Vec<i64>,Error> {
-    let mut masses = Vec::new();
-    for (idx, line) in input.lines().enumerate() {
-        let n = line
-            .parse()
-            .map_err(|e| format!("Error on line {} of input: {}", idx+1, e))?;
-        masses.push(n);
-    }
-    Ok(masses)
+    lines(input)
+        .map(|line| line.parse_at(line.text, "a mass"))
+        .collect()
 }
 
 fn fuel_req(mass: i64) -> i64 {
@@ -1,42 +1,57 @@
 use crate::error::Error;
-use crate::support::intcode::{ Intcode, parse_intcode_ops };
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::Answer;
+use crate::support::intcode::{ Intcode, Poke, parse_intcode_ops };
 
-pub fn part1(input: &str) -> Result<(),Error> {
-    let mut ops = parse_intcode_ops(input)?;
-    ops[1] = 12;
-    ops[2] = 2;
-    println!("Star 1: {}", run_program(ops)?);
-    Ok(())
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 2;
+    const TITLE: &'static str = "1202 Program Alarm";
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
 }
 
-pub fn part2(input: &str) -> Result<(),Error> {
+/// Restore the "1202 program alarm" state (and make any pokes we're
+/// given on top of that) before running the program.
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
     let ops = parse_intcode_ops(input)?;
-    let answer = run_programs(ops, 19690720)
-        .map(|(a,b)| 100 * a + b)
-        .map(|n| n.to_string())?;
-    println!("Star 2: {}", answer);
-    Ok(())
+    let mut intcode = with_noun_and_verb(ops, 12, 2);
+    pokes.apply(&mut intcode)?;
+    Ok(run_program(intcode)?.into())
+}
+
+pub fn part2(input: &str) -> Result<Answer,Error> {
+    let ops = parse_intcode_ops(input)?;
+    let (a,b) = run_programs(ops, 19690720)?;
+    Ok((100 * a + b).into())
 }
 
 fn run_programs(ops: Vec<i64>, answer: i64) -> Result<(i64,i64),Error> {
     for a in 0..=99 {
         for b in 0..=99 {
-            let mut ops = ops.clone();
-            ops[1] = a;
-            ops[2] = b;
-            let result = run_program(ops)?;
+            let result = run_program(with_noun_and_verb(ops.clone(), a, b))?;
             if result == answer {
                 return Ok((a,b))
             }
         }
     }
-    Err(err!("No answer found"))
+    Err(Error::no_solution("No answer found"))
+}
+
+/// The program with the noun and verb put in addresses 1 and 2.
+fn with_noun_and_verb(ops: Vec<i64>, noun: i64, verb: i64) -> Intcode {
+    Intcode::with_pokes(ops, &[Poke::new(1, noun), Poke::new(2, verb)])
 }
 
-fn run_program(ops: Vec<i64>) -> Result<i64,Error> {
-    let mut intcode = Intcode::new(ops);
+/// Run the program, handing back what's left in address 0.
+fn run_program(mut intcode: Intcode) -> Result<i64,Error> {
     if intcode.step()?.is_some() {
-        panic!("Unexpected instruction: cannot handle")
+        return Err(Error::intcode("The program asked for input or gave output, which day 2's programs shouldn't"))
     }
     Ok(intcode.get_op(0))
 }
@@ -1,24 +1,33 @@
 use crate::error::Error;
+use crate::days::{ Solution, Stars, NoArgs };
+use crate::support::{ Answer, Point, Direction };
+use crate::support::parse::lines;
 use std::collections::HashMap;
-use self::Direction::*;
 
-pub fn part1(input: &str) -> Result<(),Error> {
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 3;
+    const TITLE: &'static str = "Crossed Wires";
+    type Args = NoArgs;
+    fn solve(input: &str, _: NoArgs, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<Answer,Error> {
     let all_paths = parse_wires(input)?;
 
     // Draw wires on grid:
     let mut seen = HashMap::new();
     for (id,paths) in all_paths.iter().enumerate() {
-        let mut x: i32 = 0;
-        let mut y: i32 = 0;
+        let mut point = Point::ORIGIN;
         for path in paths {
             for _ in 0..path.count {
-                match path.direction {
-                    Up => { y -= 1 },
-                    Down => { y += 1 },
-                    Left => { x -= 1 },
-                    Right => { x += 1 }
-                }
-                let v = seen.entry((x,y)).or_insert(Vec::new());
+                point = point.step(path.direction);
+                let v = seen.entry(point).or_insert(Vec::new());
                 v.push(id);
                 v.sort();
                 v.dedup();
@@ -27,38 +36,31 @@ pub fn part1(input: &str) -> Result<(),Error> {
     }
 
     // Find closest intersection:
-    let mut dist: i32 = std::i32::MAX;
-    for ((x,y), values) in seen {
-        let this_dist = x.abs() + y.abs();
+    let mut dist = std::i64::MAX;
+    for (point, values) in seen {
+        let this_dist = point.manhattan(Point::ORIGIN);
         if values.len() > 1 && this_dist < dist {
             dist = this_dist
         }
     }
 
-    println!("Star 1: {}", dist);
-    Ok(())
+    Ok(dist.into())
 }
 
-pub fn part2(input: &str) -> Result<(),Error> {
+pub fn part2(input: &str) -> Result<Answer,Error> {
     let all_paths = parse_wires(input)?;
 
     // Draw wires on grid, tracking current distance travelled
     // by each wire as we go (keeping smallest only):
     let mut seen = HashMap::new();
     for (id,paths) in all_paths.iter().enumerate() {
-        let mut x: i32 = 0;
-        let mut y: i32 = 0;
+        let mut point = Point::ORIGIN;
         let mut d: usize = 0;
         for path in paths {
             for _ in 0..path.count {
                 d += 1;
-                match path.direction {
-                    Up => { y -= 1 },
-                    Down => { y += 1 },
-                    Left => { x -= 1 },
-                    Right => { x += 1 }
-                }
-                let m = seen.entry((x,y)).or_insert(HashMap::new());
+                point = point.step(path.direction);
+                let m = seen.entry(point).or_insert(HashMap::new());
                 m.entry(id).or_insert(d);
             }
         }
@@ -70,31 +72,28 @@ pub fn part2(input: &str) -> Result<(),Error> {
         .filter(|(_,v)| v.len() > 1)
         .map(|(_,v)| v.values().sum::<usize>())
         .min()
-        .map(|v| v.to_string())
-        .unwrap_or("Unknown".to_owned());
-    println!("Star 2: {}", min_intersection_d);
+        .ok_or_else(|| Error::no_solution("The wires never cross"))?;
 
-    Ok(())
+    Ok(min_intersection_d.into())
 }
 
 fn parse_wires(input: &str) -> Result<Vec<Vec<Path>>,Error> {
     let mut all_paths = vec![];
-    for line in input.trim().lines() {
+    for line in lines(input) {
         let mut paths = vec![];
-        for path in line.trim().split(",") {
-            let (direction, count) = path.split_at(1);
-            let count = count.parse()?;
-            let direction = match direction {
-                "U" => Ok(Up),
-                "D" => Ok(Down),
-                "L" => Ok(Left),
-                "R" => Ok(Right),
-                _ => Err(err!("Invalid direction"))
+        for path in line.text.split(',').map(|p| p.trim()) {
+            let direction = match path.chars().next() {
+                Some('U') => Ok(Direction::Up),
+                Some('D') => Ok(Direction::Down),
+                Some('L') => Ok(Direction::Left),
+                Some('R') => Ok(Direction::Right),
+                _ => Err(line.error_at(path, format!("Expected a direction (U, D, L or R) but got '{}'", path)))
             }?;
+            let count = line.parse_at(&path[1..], "a distance")?;
             paths.push(Path {
                 direction,
                 count
-            })
+            });
         }
         all_paths.push(paths);
     }
@@ -106,11 +105,3 @@ struct Path {
     direction: Direction,
     count: usize
 }
-
-#[derive(Clone,Copy,Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right
-}
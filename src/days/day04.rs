@@ -1,17 +1,41 @@
 use itertools::Itertools;
+use structopt::StructOpt;
 use crate::error::Error;
-use crate::support::digits;
+use crate::days::{ Solution, Stars };
+use crate::support::{ digits, Answer };
 
-pub fn part1(low: usize, high: usize) -> Result<(),Error> {
+pub struct Puzzle;
+
+/// The puzzle input is just a range of numbers, which defaults
+/// to the one that I was given.
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    #[structopt(default_value = "128392", help = "The first number in the range")]
+    low: usize,
+    #[structopt(default_value = "643281", help = "The last number in the range")]
+    high: usize
+}
+
+impl Solution for Puzzle {
+    const DAY: usize = 4;
+    const TITLE: &'static str = "Secure Container";
+    const TAKES_INPUT: bool = false;
+    type Args = Args;
+    fn solve(_: &str, Args { low, high }: Args, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(low, high))?;
+        stars.part(2, || part2(low, high))?;
+        Ok(())
+    }
+}
+
+pub fn part1(low: usize, high: usize) -> Result<Answer,Error> {
     let valid_count = (low..=high).filter(|&n| part1_test(n)).count();
-    println!("Star 1: {}", valid_count);
-    Ok(())
+    Ok(valid_count.into())
 }
 
-pub fn part2(low: usize, high: usize) -> Result<(),Error> {
+pub fn part2(low: usize, high: usize) -> Result<Answer,Error> {
     let valid_count = (low..=high).filter(|&n| part2_test(n)).count();
-    println!("Star 2: {}", valid_count);
-    Ok(())
+    Ok(valid_count.into())
 }
 
 fn part1_test(n: usize) -> bool {
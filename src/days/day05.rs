@@ -1,18 +1,29 @@
 use crate::error::Error;
-use crate::support::intcode::{ Intcode, Outcome, parse_intcode_ops };
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::Answer;
+use crate::support::intcode::{ Intcode, Outcome };
 
-pub fn part1(input: &str) -> Result<(),Error> {
-    let ops = parse_intcode_ops(input)?;
-    let intcode = Intcode::new(ops);
-    println!("Star 1: {}", run_intcode_with_input(intcode, 1)?);
-    Ok(())
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 5;
+    const TITLE: &'static str = "Sunny with a Chance of Asteroids";
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let intcode = pokes.machine(input)?;
+    Ok(run_intcode_with_input(intcode, 1)?.into())
 }
 
-pub fn part2(input: &str) -> Result<(),Error> {
-    let ops = parse_intcode_ops(input)?;
-    let intcode = Intcode::new(ops);
-    println!("Star 2: {}", run_intcode_with_input(intcode, 5)?);
-    Ok(())
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let intcode = pokes.machine(input)?;
+    Ok(run_intcode_with_input(intcode, 5)?.into())
 }
 
 fn run_intcode_with_input(mut intcode: Intcode, input: i64) -> Result<i64,Error> {
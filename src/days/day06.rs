@@ -1,29 +1,57 @@
 use crate::error::Error;
-use std::collections::HashMap;
+use crate::days::{ Solution, Stars, NoArgs };
+use crate::support::Answer;
+use crate::support::parse::lines;
+use std::collections::{ HashMap, HashSet };
 
-/// Part 1 and 2 combined
-pub fn both_parts(input: &str) -> Result<(),Error> {
-    let orbits = parse_input(input)?;
+pub struct Puzzle;
 
-    // What is orbiting what?
-    let mut object_orbits: HashMap<String,String> = HashMap::new();
-    for (a,b) in orbits {
-        object_orbits.insert(b, a);
+impl Solution for Puzzle {
+    const DAY: usize = 6;
+    const TITLE: &'static str = "Universal Orbit Map";
+    type Args = NoArgs;
+    fn solve(input: &str, _: NoArgs, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
     }
+}
+
+pub fn part1(input: &str) -> Result<Answer,Error> {
+    let object_orbits = parse_object_orbits(input)?;
 
     // Count up the orbits
-    println!("Star 1: {}", get_orbit_counts(&object_orbits));
+    let orbit_counts = get_orbit_counts(&object_orbits);
+    Ok(orbit_counts.into())
+}
+
+pub fn part2(input: &str) -> Result<Answer,Error> {
+    let object_orbits = parse_object_orbits(input)?;
+    for &object in ["YOU", "SAN"].iter() {
+        if !object_orbits.contains_key(object) {
+            return Err(Error::parse_somewhere(format!("Expected {} to be orbiting something but it isn't in the map", object)))
+        }
+    }
 
     // Get he distance to santa.
-    let distance_to_santa = get_distance_to_santa(&object_orbits);
-    println!("Star 2: {}", distance_to_santa);
+    let distance_to_santa = get_distance_to_santa(&object_orbits)
+        .ok_or_else(|| Error::no_solution("YOU and SAN don't orbit anything in common"))?;
+    Ok(distance_to_santa.into())
+}
 
-    Ok(())
+/// What is orbiting what?
+fn parse_object_orbits(input: &str) -> Result<HashMap<String,String>,Error> {
+    let orbits = parse_input(input)?;
+    let mut object_orbits: HashMap<String,String> = HashMap::new();
+    for (a,b) in orbits {
+        object_orbits.insert(b, a);
+    }
+    Ok(object_orbits)
 }
 
 /// How many orbits do you have to hop to get to santa? This
-/// assumes that everything ultiamtely orbits COM.
-fn get_distance_to_santa(object_orbits: &HashMap<String,String>) -> usize {
+/// assumes that everything ultiamtely orbits COM, and is None if not.
+fn get_distance_to_santa(object_orbits: &HashMap<String,String>) -> Option<usize> {
 
     // Step 1: How far away are objects that Santa is orbiting?
     let mut distances_from_santa = HashMap::new();
@@ -47,15 +75,10 @@ fn get_distance_to_santa(object_orbits: &HashMap<String,String>) -> usize {
 
     // Step 3: Find the object common to both of the above and add
     // the scores together to get the total hops.
-    let mut min_hops = std::usize::MAX;
-    for (object, distance_a) in distances_from_you {
-        if let Some(distance_b) = distances_from_santa.get(object) {
-            let total = distance_a + distance_b;
-            min_hops = min_hops.min(total);
-        }
-    }
-
-    min_hops
+    distances_from_you
+        .into_iter()
+        .filter_map(|(object, distance_a)| distances_from_santa.get(object).map(|distance_b| distance_a + distance_b))
+        .min()
 }
 
 /// Naively count all orbits from every object to get a total
@@ -82,9 +105,20 @@ fn iterate_orbits<'a>(start: &'a str, object_orbits: &'a HashMap<String,String>)
 
 fn parse_input(input: &str) -> Result<Vec<(String,String)>,Error> {
     let mut results = vec![];
-    for (idx,line) in input.trim().lines().enumerate() {
-        let pos = line.find(')').ok_or_else(|| err!("Cannot parse line {}", idx+1))?;
-        results.push((line[0..pos].trim().to_owned(), line[pos+1..].trim().to_owned()));
+    let mut orbiting = HashSet::new();
+    for line in lines(input) {
+        let (a, b) = line.text.split_once(')')
+            .ok_or_else(|| line.error_at(line.text, format!("Expected a line like 'A)B' but got '{}'", line.text)))?;
+        let (a, b) = (a.trim(), b.trim());
+        for name in [a, b].iter() {
+            if name.is_empty() {
+                return Err(line.error_at(name, "Expected the name of an object on both sides of ')'"))
+            }
+        }
+        if !orbiting.insert(b) {
+            return Err(line.error_at(b, format!("'{}' orbits more than one object", b)))
+        }
+        results.push((a.to_owned(), b.to_owned()));
     }
     Ok(results)
 }
\ No newline at end of file
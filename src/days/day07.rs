@@ -1,9 +1,25 @@
 use crate::error::Error;
-use crate::support::intcode::{ Intcode, Outcome, parse_intcode_ops };
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::Answer;
+use crate::support::intcode::{ Intcode, Outcome };
 use itertools::Itertools;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let intcode = Intcode::new(parse_intcode_ops(input)?);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 7;
+    const TITLE: &'static str = "Amplification Circuit";
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+/// Any pokes are made to the program that every amplifier runs.
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let intcode = pokes.machine(input)?;
 
     // What combination of inputs in the range [0,4] produces the
     // largest final output?
@@ -13,7 +29,11 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
             star1_output = run_amplifiers_repeatedly_with_input(vec![a,b,c,d,e], &intcode)?.max(star1_output);
         }
     }
-    println!("Star 1: {}", star1_output);
+    Ok(star1_output.into())
+}
+
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let intcode = pokes.machine(input)?;
 
     // What combination of inputs in the range [5,9] produces the
     // largest final output?
@@ -23,9 +43,7 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
             star2_output = run_amplifiers_repeatedly_with_input(vec![a,b,c,d,e], &intcode)?.max(star2_output);
         }
     }
-    println!("Star 2: {}", star2_output);
-
-    Ok(())
+    Ok(star2_output.into())
 }
 
 /// This seems to work for parts 1 and 2; keep running amplifiers until they halt and return
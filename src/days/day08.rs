@@ -1,44 +1,79 @@
+use structopt::StructOpt;
 use crate::error::Error;
+use crate::days::{ Solution, Stars };
+use crate::support::{ read_letters, Answer, Image, Picture };
+use crate::support::render::render;
+use crate::support::parse::single_line;
 
-pub fn part1(input: &str, width: usize, height: usize) -> Result<(), Error> {
+pub struct Puzzle;
+
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    #[structopt(long, default_value = "25", help = "The image width")]
+    image_width: usize,
+    #[structopt(long, default_value = "6",  help = "The image height")]
+    image_height: usize
+}
+
+impl Solution for Puzzle {
+    const DAY: usize = 8;
+    const TITLE: &'static str = "Space Image Format";
+    const DRAWS_PICTURES: bool = true;
+    type Args = Args;
+    fn solve(input: &str, Args { image_width, image_height }: Args, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, image_width, image_height))?;
+        stars.part(2, || part2(input, image_width, image_height))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str, width: usize, height: usize) -> Result<Answer, Error> {
     // Find layer with least 0's:
-    let ls = parse_data(input, width, height);
+    let ls = parse_data(input, width, height, 9)?;
     let l = ls
         .iter()
         .min_by_key(|l| count_digit(l,0))
-        .unwrap();
+        .ok_or_else(|| err!("The image has no layers"))?;
 
-    println!("Star 1: {}", count_digit(l, 1) * count_digit(l, 2));
-    Ok(())
+    Ok((count_digit(l, 1) * count_digit(l, 2)).into())
 }
 
-pub fn part2(input: &str, width: usize, height: usize) -> Result<(), Error> {
-    let layers = parse_data(input, width, height);
+pub fn part2(input: &str, width: usize, height: usize) -> Result<Answer, Error> {
+    let layers = parse_data(input, width, height, 2)?;
     let size = width * height;
 
     // Merge layers (0 black, 1 white, 2 transparent):
     let l: Vec<u8> = (0..size)
-        .map(|i| layers.iter().map(|l| l[i]).filter(|&n| n != 2).next().unwrap())
-        .collect();
-
-    // Print output to console:
-    println!("Star 2:");
-    for row in l.chunks_exact(width) {
-        row.iter().for_each(|&n| print!("{}", if n == 1 { '■' } else { ' '}));
-        println!();
-    }
+        .map(|i| layers.iter().map(|l| l[i]).filter(|&n| n != 2).next()
+            .ok_or_else(|| err!("The pixel at ({},{}) is transparent in every layer", i % width, i / width)))
+        .collect::<Result<_,_>>()?;
 
-    Ok(())
+    // White pixels are the lit ones:
+    let pixels = l.into_iter().map(|n| n == 1).collect();
+    let image = Image::new(width, pixels);
+    render(&Picture::from(&image))?;
+    // Pictures that aren't letters are the answer as they stand:
+    Ok(read_letters(&image).map(Answer::from).unwrap_or_else(|_| image.into()))
 }
 
 fn count_digit(layer: &[u8], digit: u8) -> usize {
     layer.iter().filter(|&&n| n == digit).count()
 }
 
-fn parse_data(input: &str, width: usize, height: usize) -> Vec<Vec<u8>> {
-    input.trim()
-         .as_bytes()
-         .chunks_exact(width*height)
-         .map(|c| c.into_iter().map(|b| b-48).collect())
-         .collect()
+/// Split the image into layers of digits. Part 1 only counts them, but
+/// to draw the image each has to be 0 (black), 1 (white) or 2 (transparent),
+/// so `max_digit` says how high they can go.
+fn parse_data(input: &str, width: usize, height: usize, max_digit: u8) -> Result<Vec<Vec<u8>>,Error> {
+    let size = width * height;
+    if size == 0 {
+        return Err(err!("The image should be at least 1x1 but it's {}x{}", width, height))
+    }
+    let line = single_line(input)?;
+    let pixels = line.digits(max_digit)?;
+    let left_over = pixels.len() % size;
+    if left_over != 0 {
+        let partial_layer = &line.text[pixels.len() - left_over..];
+        return Err(line.error_at(partial_layer, format!("Expected whole {}x{} layers but the last is only {} of {} pixels", width, height, left_over, size)))
+    }
+    Ok(pixels.chunks_exact(size).map(|c| c.to_vec()).collect())
 }
\ No newline at end of file
@@ -1,19 +1,31 @@
 use crate::error::Error;
-use crate::support::intcode::{ parse_intcode_ops, Intcode, Outcome };
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::Answer;
+use crate::support::intcode::Outcome;
 
-pub fn part1(input: &str) -> Result<(), Error> {
-    println!("Star 1: {}", run_with_input(input, 1)?);
-    Ok(())
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 9;
+    const TITLE: &'static str = "Sensor Boost";
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer, Error> {
+    Ok(run_with_input(input, pokes, 1)?.into())
 }
 
-pub fn part2(input: &str) -> Result<(), Error> {
-    println!("Star 2: {}", run_with_input(input, 2)?);
-    Ok(())
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer, Error> {
+    Ok(run_with_input(input, pokes, 2)?.into())
 }
 
-pub fn run_with_input(ops: &str, input: i64) -> Result<i64, Error> {
-    let ops = parse_intcode_ops(ops)?;
-    let mut intcode = Intcode::new(ops);
+pub fn run_with_input(ops: &str, pokes: &Pokes, input: i64) -> Result<i64, Error> {
+    let mut intcode = pokes.machine(ops)?;
     while let Some(outcome) = intcode.step()? {
         match outcome {
             Outcome::NeedsInput(provider) => {
@@ -24,13 +36,14 @@ pub fn run_with_input(ops: &str, input: i64) -> Result<i64, Error> {
             }
         }
     }
-    Err(err!("Expected an output but program finished first"))
+    Err(Error::intcode("Expected an output but program finished first"))
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::support::intcode::{ parse_intcode_ops, Intcode };
 
     #[test]
     fn output_large_middle_number() {
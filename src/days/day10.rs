@@ -1,20 +1,39 @@
 use crate::error::Error;
+use crate::days::{ Solution, Stars, NoArgs };
+use crate::support::{ Answer, AsciiMap, Whitespace };
 use std::collections::{ HashMap, HashSet };
 use std::f64::consts::{ FRAC_PI_2, PI };
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let coords = parse_asteroid_coords(input);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 10;
+    const TITLE: &'static str = "Monitoring Station";
+    type Args = NoArgs;
+    fn solve(input: &str, _: NoArgs, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<Answer,Error> {
+    let coords = parse_asteroid_coords(input)?;
 
     // Where do we want our station?
-    let (best_coords, best_n) = find_best_asteroid_for_station(&coords);
-    println!("Star 1: {} ({},{})", best_n, best_coords.0, best_coords.1);
+    let (_, best_n) = find_best_asteroid_for_station(&coords);
+    Ok(best_n.into())
+}
+
+pub fn part2(input: &str) -> Result<Answer,Error> {
+    let coords = parse_asteroid_coords(input)?;
+    let (best_coords, _) = find_best_asteroid_for_station(&coords);
 
     // Now, which coords are hit first by a laser?
     let visible_from_best: Vec<_> = coords_encountered_by_laser(best_coords, &coords);
-    let t = visible_from_best[199];
-    println!("Star 2: {}", t.0 * 100 + t.1);
-
-    Ok(())
+    let t = *visible_from_best.get(199)
+        .ok_or_else(|| Error::no_solution(format!("Expected 200 asteroids to be visible from the station but there are {}", visible_from_best.len())))?;
+    Ok((t.0 * 100 + t.1).into())
 }
 
 fn coords_encountered_by_laser((x,y): (i64,i64), coords: &HashSet<(i64,i64)>) -> Vec<(i64,i64)> {
@@ -45,7 +64,7 @@ fn find_best_asteroid_for_station(coords: &HashSet<(i64,i64)>) -> ((i64,i64),usi
         let visible = find_visible_asteroids_for(xy, coords).count();
         visible_counts.insert(xy, visible);
     }
-    visible_counts.into_iter().max_by_key(|(_,n)| *n).unwrap()
+    visible_counts.into_iter().max_by_key(|(_,n)| *n).expect("there's always an asteroid")
 }
 
 fn find_visible_asteroids_for<'a>((x,y): (i64,i64), coords: &'a HashSet<(i64,i64)>) -> impl Iterator<Item=(i64,i64)> + 'a {
@@ -79,16 +98,17 @@ fn get_coords_between((x1,y1): (i64,i64), (x2,y2): (i64,i64)) -> impl Iterator<I
         })
 }
 
-fn parse_asteroid_coords(input: &str) -> HashSet<(i64,i64)> {
-    let mut coords = HashSet::new();
-    for (y,line) in input.trim().lines().enumerate() {
-        for (x,c) in line.trim().chars().enumerate() {
-            if c != '.' {
-                coords.insert((x as i64, y as i64));
-            }
-        }
+fn parse_asteroid_coords(input: &str) -> Result<HashSet<(i64,i64)>,Error> {
+    let map = AsciiMap::parse(input, Whitespace::Strip, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None
+    })?;
+    let coords: HashSet<_> = map.iter().filter(|&(_,&asteroid)| asteroid).map(|(p,_)| (p.x, p.y)).collect();
+    if coords.is_empty() {
+        return Err(Error::parse_somewhere("Expected some asteroids ('#') in the map but there aren't any"))
     }
-    coords
+    Ok(coords)
 }
 
 #[cfg(test)]
@@ -191,7 +211,7 @@ mod test {
         ];
 
         for (input, pos, n) in inputs {
-            let coords = parse_asteroid_coords(input);
+            let coords = parse_asteroid_coords(input).unwrap();
             let (best_coords, best_n) = find_best_asteroid_for_station(&coords);
             assert_eq!(best_coords, pos);
             assert_eq!(best_n, n);
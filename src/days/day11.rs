@@ -1,68 +1,86 @@
 use crate::error::Error;
-use crate::support::intcode::{ parse_intcode_ops };
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::{ read_letters, Answer, Image, Picture, Point, Direction, BoundingBox, SparseGrid };
+use crate::support::render::render;
+use crate::support::animate;
 use self::robot::{ Robot, Colour, Outcome, Direction as TurnDirection };
-use std::collections::HashMap;
-use std::io::Write;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let ops = parse_intcode_ops(input)?;
-    let r = Robot::new(ops);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 11;
+    const TITLE: &'static str = "Space Police";
+    const DRAWS_PICTURES: bool = true;
+    const ANIMATES: bool = true;
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let r = Robot::new(pokes.machine(input)?);
 
-    let mut canvas: HashMap<(i64,i64),Colour> = HashMap::new();
-    run_robot(r.clone(), &mut canvas)?;
-    println!("Star 1: {}", canvas.len());
+    let mut canvas = SparseGrid::new();
+    run_robot(r, &mut canvas)?;
+    let painted = canvas.len();
+    Ok(painted.into())
+}
 
-    let mut canvas: HashMap<(i64,i64),Colour> = HashMap::new();
-    canvas.insert((0,0), Colour::White);
-    run_robot(r.clone(), &mut canvas)?;
-    println!("Star 2:");
-    print_canvas(&canvas);
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let r = Robot::new(pokes.machine(input)?);
 
-    Ok(())
+    let mut canvas = SparseGrid::new();
+    canvas.insert(Point::ORIGIN, Colour::White);
+    run_robot(r, &mut canvas)?;
+    let image = canvas_image(&canvas);
+    render(&Picture::from(&image))?;
+    // Pictures that aren't letters are the answer as they stand:
+    Ok(read_letters(&image).map(Answer::from).unwrap_or_else(|_| image.into()))
 }
 
-/// Display the current canvas
-fn print_canvas(canvas: &HashMap<(i64,i64),Colour>) {
-    let [(x1,y1),(x2,y2)] = canvas.keys().fold([(std::i64::MAX,std::i64::MAX),(std::i64::MIN,std::i64::MIN)], |[(x1,y1),(x2,y2)], &(x,y)| {
-        [(x1.min(x), y1.min(y)), (x2.max(x), y2.max(y))]
-    });
-    let stdout = std::io::stdout();
-    let mut stdout = stdout.lock();
-    for y in y1..=y2 {
-        for x in x1..=x2 {
-            if let Some(Colour::White) = canvas.get(&(x,y)) {
-                stdout.write_all(b"#").unwrap();
-            } else {
-                stdout.write_all(b" ").unwrap();
-            }
-        }
-        stdout.write_all(b"\n").unwrap();
-    }
+/// An image of the painted part of the canvas
+fn canvas_image(canvas: &SparseGrid<Colour>) -> Image {
+    let bbox = canvas.bounding_box().unwrap_or(BoundingBox { min: Point::ORIGIN, max: Point::ORIGIN });
+    Image::from_fn(bbox.width(), bbox.height(), |x,y| {
+        let p = bbox.min + Point::new(x as i64, y as i64);
+        canvas.get(p) == Some(&Colour::White)
+    })
+}
+
+/// The canvas with the robot on it, for watching it paint.
+fn draw_canvas(canvas: &SparseGrid<Colour>, robot: Point, direction: Direction) -> String {
+    let bbox = BoundingBox::around(canvas.points().chain(Some(robot))).unwrap();
+    bbox.render(|p| match canvas.get(p) {
+        _ if p == robot => direction.arrow(),
+        Some(Colour::White) => '#',
+        Some(Colour::Black) => '.',
+        None => ' '
+    })
 }
 
 /// Run a robot given some canvas (starting the robot at 0,0). It will paint onto
 /// the canvas, and we'll return an Error if something goes wrong.
-fn run_robot(mut r: Robot, canvas: &mut HashMap<(i64,i64),Colour>) -> Result<(),Error> {
-    let mut coords: (i64,i64) = (0,0);
-    let mut direction: Direction = Direction::Up;
+fn run_robot(mut r: Robot, canvas: &mut SparseGrid<Colour>) -> Result<(),Error> {
+    let mut coords = Point::ORIGIN;
+    let mut direction = Direction::Up;
     while let Some(outcome) = r.step()? {
         match outcome {
             Outcome::PaintPanel(c) => {
                 canvas.insert(coords,c);
             },
             Outcome::Turn(d) => {
-                match d {
-                    TurnDirection::Left => {
-                        direction.rotate_left();
-                    },
-                    TurnDirection::Right => {
-                        direction.rotate_right();
-                    }
-                }
-                coords = direction.move_coords(coords);
+                direction = match d {
+                    TurnDirection::Left => direction.left(),
+                    TurnDirection::Right => direction.right()
+                };
+                coords = coords.step(direction);
+                animate::frame(|| draw_canvas(canvas, coords, direction))?;
             },
             Outcome::ProvidePanelColour(p) => {
-                let c = canvas.get(&coords).map(|c| *c).unwrap_or(Colour::Black);
+                let c = canvas.get(coords).copied().unwrap_or(Colour::Black);
                 r.provide_input(p.value(c))?;
             }
         }
@@ -70,37 +88,6 @@ fn run_robot(mut r: Robot, canvas: &mut HashMap<(i64,i64),Colour>) -> Result<(),
     Ok(())
 }
 
-/// What direction are we moving in
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right
-}
-impl Direction {
-    fn rotate_left(&mut self) {
-        *self = match self {
-            Direction::Up => Direction::Left,
-            Direction::Left => Direction::Down,
-            Direction::Down => Direction::Right,
-            Direction::Right => Direction::Up,
-        }
-    }
-    fn rotate_right(&mut self) {
-        self.rotate_left();
-        self.rotate_left();
-        self.rotate_left();
-    }
-    fn move_coords(&self, (x,y): (i64,i64)) -> (i64,i64) {
-        match self {
-            Direction::Up => (x,y-1),
-            Direction::Left => (x-1,y),
-            Direction::Down => (x,y+1),
-            Direction::Right => (x+1,y),
-        }
-    }
-}
-
 /// A robot that turns and moves, paints panels, and asks for panel colours.
 pub mod robot {
 
@@ -119,8 +106,8 @@ pub mod robot {
     }
 
     impl Robot {
-        pub fn new(ops: Vec<i64>) -> Robot {
-            Robot { intcode: Intcode::new(ops), is_second_output: false }
+        pub fn new(intcode: Intcode) -> Robot {
+            Robot { intcode, is_second_output: false }
         }
         pub fn provide_input(&mut self, value: ProvideInputValue) -> Result<(),Error> {
             self.intcode.provide_input(value.0)
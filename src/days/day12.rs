@@ -1,17 +1,44 @@
 use crate::error::Error;
+use structopt::StructOpt;
+use crate::days::{ Solution, Stars };
+use crate::support::{ Answer, Progress };
+use crate::support::parse::lines;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use num::integer::lcm;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let moons = parse_moons(input);
+pub struct Puzzle;
 
-    // step 1000 cycles and see what the total energy is
-    let mut moons = moons.clone();
-    for _ in 0..1000 {
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    #[structopt(long, default_value = "1000", help = "How many steps to simulate in part 1")]
+    steps: usize
+}
+
+impl Solution for Puzzle {
+    const DAY: usize = 12;
+    const TITLE: &'static str = "The N-Body Problem";
+    type Args = Args;
+    fn solve(input: &str, Args { steps }: Args, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, steps))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str, steps: usize) -> Result<Answer,Error> {
+    let mut moons = parse_moons(input)?;
+
+    // step 1000 cycles (unless told otherwise) and see what the total energy is
+    for _ in 0..steps {
         step_moons(&mut moons);
     }
-    println!("Star 1: {}", calculate_energy(&moons));
+    let energy = calculate_energy(&moons);
+    Ok(energy.into())
+}
+
+pub fn part2(input: &str) -> Result<Answer,Error> {
+    let moons = parse_moons(input)?;
 
     // each dimension is independent, and will repeat from 0, so
     // find the cycles taken for each and then find the lowest
@@ -20,9 +47,8 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
         .into_iter()
         .map(|&d| find_dimension_repeating(moons.clone(), d))
         .collect();
-    println!("Star 2: {}", repeats.into_iter().fold(1,lcm));
-
-    Ok(())
+    let repeats_after = repeats.into_iter().fold(1,lcm);
+    Ok(repeats_after.into())
 }
 
 fn find_dimension_repeating(mut sim: Vec<Moon>, dimension: Dimension) -> usize {
@@ -33,9 +59,11 @@ fn find_dimension_repeating(mut sim: Vec<Moon>, dimension: Dimension) -> usize {
     };
     let orig: Vec<(i64,i64)> = sim.iter().map(dim_values).collect();
     let mut n = 0;
+    let mut progress = Progress::new(format!("Day 12 part 2, {:?}", dimension), "steps");
     loop {
         step_moons(&mut sim);
         n += 1;
+        progress.tick();
         let pvs: Vec<_> = sim.iter().map(dim_values).collect();
         if pvs == orig { return n }
     }
@@ -78,15 +106,17 @@ fn apply_gravity_single(a_pos: &i64, a_vel: &mut i64, b_pos: &i64, b_vel: &mut i
     *b_vel -= 1;
 }
 
-fn parse_moons(input: &str) -> Vec<Moon> {
-    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<x=(-?\d+),\s*y=(-?\d+),\s*z=(-?\d+)>").unwrap());
+fn parse_moons(input: &str) -> Result<Vec<Moon>,Error> {
+    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^<x=(\S+?),\s*y=(\S+?),\s*z=(\S+?)>$").unwrap());
     let mut moons: Vec<Moon> = vec![];
-    for cap in RE.captures_iter(input) {
+    for line in lines(input) {
+        let cap = RE.captures(line.text)
+            .ok_or_else(|| line.error_at(line.text, format!("Expected a moon like '<x=1, y=2, z=3>' but got '{}'", line.text)))?;
         moons.push(Moon {
             position: Point {
-                x: cap[1].parse().unwrap(),
-                y: cap[2].parse().unwrap(),
-                z: cap[3].parse().unwrap(),
+                x: line.parse_at(&cap[1], "a number")?,
+                y: line.parse_at(&cap[2], "a number")?,
+                z: line.parse_at(&cap[3], "a number")?,
             },
             velocity: Point {
                 x: 0,
@@ -95,7 +125,7 @@ fn parse_moons(input: &str) -> Vec<Moon> {
             }
         })
     }
-    moons
+    Ok(moons)
 }
 
 #[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
@@ -1,36 +1,69 @@
 use crate::error::Error;
+use crate::days::{ Solution, Stars, Pokes };
 use crate::support::intcode::parse_intcode_ops;
+use crate::support::{ Answer, Picture, Point, BoundingBox };
+use crate::support::render::{ render, BLACK, DARK_GREY, LIGHT_GREY, WHITE };
+use crate::support::animate;
 use self::breakout::{ Breakout, Outcome, Tile, Direction };
 use std::collections::HashMap;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 13;
+    const TITLE: &'static str = "Care Package";
+    const DRAWS_PICTURES: bool = true;
+    const ANIMATES: bool = true;
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+/// Run the game and count the blocks left.
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
     let ops = parse_intcode_ops(input)?;
 
-    // Star 1: run the game and count the blocks left:
-    {
-        let mut game = Breakout::new(ops.clone());
+    let star1 = {
+        let mut game = Breakout::new(ops, pokes)?;
         let mut image = HashMap::new();
         while let Some(output) = game.step()? {
-            if let Outcome::Draw { x, y, tile } = output {
-                image.insert((x, y), tile);
+            match output {
+                Outcome::Draw { x, y, tile } => {
+                    image.insert((x, y), tile);
+                },
+                // Only asked for if the game's been poked into playing;
+                // leave the ball to it:
+                Outcome::MoveJoystick(provider) => {
+                    game.move_joystick(provider.value(Direction::Neutral))?;
+                },
+                Outcome::Score(_) => {}
             }
         }
+        render(&screen_picture(&image))?;
         let blocks_left = image.values().filter(|&&v| v == Tile::Block).count();
-        println!("Star 1: {}", blocks_left);
-    }
+        blocks_left
+    };
+    Ok(star1.into())
+}
 
-    // Star 2: run the game, keep the paddle under the ball and
-    // see what score we have when the game finishes:
-    {
-        let mut ops = ops;
-        ops[0] = 2;
-        let mut game = Breakout::new(ops);
+/// Run the game, keep the paddle under the ball and see what
+/// score we have when the game finishes.
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let ops = parse_intcode_ops(input)?;
+
+    let star2 = {
+        let mut game = Breakout::free_play(ops, pokes)?;
+        let mut screen = HashMap::new();
         let mut score = 0;
         let mut ball_x: i64 = 0;
         let mut paddle_x: i64 = 0;
         while let Some(outcome) = game.step()? {
             match outcome {
-                Outcome::Draw { x, tile, .. } => {
+                Outcome::Draw { x, y, tile } => {
+                    screen.insert((x, y), tile);
                     if let Tile::Ball = tile {
                         ball_x = x;
                     } else if let Tile::Paddle = tile {
@@ -41,6 +74,7 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
                     score = s;
                 },
                 Outcome::MoveJoystick(provider) => {
+                    animate::frame(|| format!("Score: {}\n{}", score, draw_screen(&screen)))?;
                     let m = if ball_x < paddle_x {
                         Direction::Left
                     } else if ball_x > paddle_x {
@@ -52,18 +86,44 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
                 }
             }
         }
-        println!("Star 2: {}", score);
-    }
+        score
+    };
+    Ok(star2.into())
+}
 
-    Ok(())
+/// The game's screen as text, for watching it being played.
+fn draw_screen(screen: &HashMap<(i64,i64),Tile>) -> String {
+    let bbox = BoundingBox::around(screen.keys().map(|&xy| Point::from(xy)))
+        .unwrap_or(BoundingBox { min: Point::ORIGIN, max: Point::ORIGIN });
+    bbox.render(|p| match screen.get(&(p.x, p.y)) {
+        None | Some(Tile::Empty) => ' ',
+        Some(Tile::Wall) => '#',
+        Some(Tile::Block) => '%',
+        Some(Tile::Paddle) => '=',
+        Some(Tile::Ball) => 'o'
+    })
+}
+
+/// What the game's screen looks like.
+fn screen_picture(screen: &HashMap<(i64,i64),Tile>) -> Picture {
+    let bbox = BoundingBox::around(screen.keys().map(|&xy| Point::from(xy)))
+        .unwrap_or(BoundingBox { min: Point::ORIGIN, max: Point::ORIGIN });
+    Picture::of_box(bbox, |p| match screen.get(&(p.x, p.y)) {
+        None | Some(Tile::Empty) => WHITE,
+        Some(Tile::Wall) => BLACK,
+        Some(Tile::Block) => LIGHT_GREY,
+        Some(Tile::Paddle) | Some(Tile::Ball) => DARK_GREY
+    })
 }
 
 /// This module implements a game of breakout using the provided intcode ops
 pub mod breakout {
 
     use crate::error::Error;
+    use crate::days::Pokes;
     use crate::support::intcode::{
         Intcode,
+        Poke,
         Outcome as IntcodeOutcome,
         ProvideInput as IntcodeProvideInput,
         ProvideInputValue as IntcodeProvideInputValue
@@ -74,8 +134,16 @@ pub mod breakout {
     }
 
     impl Breakout {
-        pub fn new(ops: Vec<i64>) -> Breakout {
-            Breakout { intcode: Intcode::new(ops) }
+        pub fn new(ops: Vec<i64>, pokes: &Pokes) -> Result<Breakout,Error> {
+            Breakout::with_pokes(Intcode::new(ops), pokes)
+        }
+        /// A game that doesn't need quarters, which are kept in address 0.
+        pub fn free_play(ops: Vec<i64>, pokes: &Pokes) -> Result<Breakout,Error> {
+            Breakout::with_pokes(Intcode::with_pokes(ops, &[Poke::new(0, 2)]), pokes)
+        }
+        fn with_pokes(mut intcode: Intcode, pokes: &Pokes) -> Result<Breakout,Error> {
+            pokes.apply(&mut intcode)?;
+            Ok(Breakout { intcode })
         }
         pub fn move_joystick(&mut self, value: ProvideInputValue) -> Result<(),Error> {
             self.intcode.provide_input(value.0)
@@ -92,12 +160,12 @@ pub mod breakout {
                     let n2 = if let Some(IntcodeOutcome::Output(n2)) = self.intcode.step()? {
                         n2
                     } else {
-                        return Err(err!("Expected 3 output values in a row, but only got 1"))
+                        return Err(Error::intcode("Expected 3 output values in a row, but only got 1"))
                     };
                     let n3 = if let Some(IntcodeOutcome::Output(n3)) = self.intcode.step()? {
                         n3
                     } else {
-                        return Err(err!("Expected 3 output values in a row, but only got 2"))
+                        return Err(Error::intcode("Expected 3 output values in a row, but only got 2"))
                     };
 
                     if n1 == -1 && n2 == 0 {
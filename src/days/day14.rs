@@ -1,18 +1,38 @@
 use crate::error::Error;
-use once_cell::sync::Lazy;
-use regex::Regex;
+use crate::days::{ Solution, Stars, NoArgs };
+use crate::support::{ Answer, Progress };
+use crate::support::parse::{ lines, Line };
 use std::collections::HashMap;
 
 const TRILLION: u128 = 1_000_000_000_000;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let reactions = parse_reactions(input);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 14;
+    const TITLE: &'static str = "Space Stoichiometry";
+    type Args = NoArgs;
+    fn solve(input: &str, _: NoArgs, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<Answer,Error> {
+    let reactions = parse_reactions(input)?;
     let recipes = make_recipes(&reactions);
 
-    println!("Star 1: {}", ore_needed_for_fuel(&recipes, 1));
-    println!("Star 2: {}", search_for_trillion(&recipes));
+    let ore_needed = ore_needed_for_fuel(&recipes, 1);
+    Ok(ore_needed.into())
+}
+
+pub fn part2(input: &str) -> Result<Answer,Error> {
+    let reactions = parse_reactions(input)?;
+    let recipes = make_recipes(&reactions);
 
-    Ok(())
+    let fuel_made = search_for_trillion(&recipes);
+    Ok(fuel_made.into())
 }
 
 /// Binary chop different fuel targets to find out how much fuel
@@ -20,7 +40,10 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
 fn search_for_trillion(recipes: &Recipes) -> u128 {
     let mut best_higher = TRILLION;
     let mut best_lower = std::u128::MIN;
+    // Each guess halves the range, so there are at most log2(TRILLION)+1 of them:
+    let mut progress = Progress::bounded("Day 14 part 2", "guesses", 128 - TRILLION.leading_zeros() as u64);
     loop {
+        progress.tick();
         let current = (best_higher - best_lower) / 2 + best_lower;
         let lower_ore = ore_needed_for_fuel(recipes, current);
         let higher_ore = ore_needed_for_fuel(recipes, current+1);
@@ -99,24 +122,99 @@ fn ore_needed_for_fuel(recipes: &Recipes, fuel_needed: u128) -> u128 {
     ore_needed
 }
 
-fn parse_reactions(input: &str) -> Vec<Reaction> {
-    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"([0-9]+) ([A-Z]+)").unwrap());
+/// Parse reactions like "7 A, 1 B => 1 C". Every chemical but ORE
+/// has to be made by exactly one of them, and one has to make FUEL.
+fn parse_reactions(input: &str) -> Result<Vec<Reaction>,Error> {
     let mut reactions: Vec<Reaction> = vec![];
-    for line in input.trim().lines() {
+    let mut made_on: HashMap<&str,usize> = HashMap::new();
+    let mut needed: Vec<(Line,&str)> = vec![];
+    let mut made_from: Vec<(&str,Line,Vec<&str>)> = vec![];
+    for line in lines(input) {
+        let (inputs, output) = line.text.split_once("=>")
+            .ok_or_else(|| line.error_at(line.text, format!("Expected a reaction like '7 A, 1 B => 1 C' but got '{}'", line.text)))?;
+        let (amount, name) = parse_chemical(&line, output)?;
+        if let Some(n) = made_on.insert(name, line.number) {
+            return Err(line.error_at(name, format!("{} is already made by the reaction on line {}", name, n)))
+        }
+        let output = Chemical { amount, name: name.to_owned() };
         let mut chemicals: Vec<Chemical> = vec![];
-        for cap in RE.captures_iter(line) {
-            chemicals.push(Chemical {
-                amount: cap[1].parse().unwrap(),
-                name: cap[2].to_owned()
-            });
+        let mut input_names = vec![];
+        for input in inputs.split(',') {
+            let (amount, input_name) = parse_chemical(&line, input)?;
+            needed.push((line, input_name));
+            input_names.push(input_name);
+            chemicals.push(Chemical { amount, name: input_name.to_owned() });
         }
-        let last = chemicals.pop().unwrap();
+        made_from.push((name, line, input_names));
         reactions.push(Reaction {
             inputs: chemicals,
-            output: last
+            output
         });
     }
-    reactions
+    if let Some((line, name)) = needed.iter().find(|(_, name)| *name != "ORE" && !made_on.contains_key(name)) {
+        return Err(line.error_at(name, format!("No reaction makes {}", name)))
+    }
+    if !made_on.contains_key("FUEL") {
+        return Err(Error::parse_somewhere("No reaction makes FUEL"))
+    }
+    if let Some((name, line, input)) = find_circle(&made_from) {
+        let message = if name == input {
+            format!("Making {} needs {} itself", name, input)
+        } else {
+            format!("Making {} needs {}, which can't be made without {}", name, input, name)
+        };
+        return Err(line.error_at(input, message))
+    }
+    Ok(reactions)
+}
+
+/// Find a reaction whose input can't be made without (eventually) its
+/// output, handing back that output along with the line and input, so
+/// that we don't go round in circles working out what's needed.
+fn find_circle<'a>(made_from: &[(&'a str,Line<'a>,Vec<&'a str>)]) -> Option<(&'a str,Line<'a>,&'a str)> {
+    let lookup: MadeFrom = made_from.iter().map(|(name, line, inputs)| (*name, (*line, inputs.as_slice()))).collect();
+    let mut finished = HashMap::new();
+    made_from.iter().find_map(|&(name, _, _)| {
+        if finished.contains_key(name) { None } else { find_circle_from(name, &lookup, &mut finished) }
+    })
+}
+
+/// Look for a circle through everything that some chemical needs,
+/// noting for each chemical seen whether we've finished with it.
+fn find_circle_from<'a>(name: &'a str, lookup: &MadeFrom<'a,'_>, finished: &mut HashMap<&'a str,bool>) -> Option<(&'a str,Line<'a>,&'a str)> {
+    finished.insert(name, false);
+    if let Some(&(line, inputs)) = lookup.get(name) {
+        for &input in inputs {
+            let circle = match finished.get(input) {
+                Some(false) => Some((name, line, input)),
+                Some(true) => None,
+                None => find_circle_from(input, lookup, finished)
+            };
+            if circle.is_some() {
+                return circle
+            }
+        }
+    }
+    finished.insert(name, true);
+    None
+}
+
+/// The line that each chemical is made on, and what it's made from.
+type MadeFrom<'a,'b> = HashMap<&'a str,(Line<'a>,&'b [&'a str])>;
+
+/// Parse an amount of some chemical, like "7 A", from part of a reaction.
+fn parse_chemical<'a>(line: &Line<'a>, part: &'a str) -> Result<(u128,&'a str),Error> {
+    let part = part.trim();
+    let (amount, name) = part.split_once(' ')
+        .ok_or_else(|| line.error_at(part, format!("Expected an amount of a chemical like '7 A' but got '{}'", part)))?;
+    let name = name.trim();
+    if !name.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(line.error_at(name, format!("Expected the name of a chemical but got '{}'", name)))
+    }
+    match line.parse_at(amount, "an amount")? {
+        0 => Err(line.error_at(amount, "Expected an amount of at least 1")),
+        amount => Ok((amount, name))
+    }
 }
 
 type Recipes<'a> = HashMap<&'a str,(u128, &'a [Chemical])>;
@@ -207,7 +305,7 @@ mod test {
             (SAMPLE5, 2210736)
         ];
         for (s, expected) in tests {
-            let actual = ore_needed_for_fuel(&make_recipes(&parse_reactions(s)), 1);
+            let actual = ore_needed_for_fuel(&make_recipes(&parse_reactions(s).unwrap()), 1);
             assert_eq!(actual, expected, "Needs {} ore but algo says {}", expected, actual);
         }
     }
@@ -220,8 +318,8 @@ mod test {
             (SAMPLE5, 460664)
         ];
         for (s, ore_needed) in tests {
-            let lower = ore_needed_for_fuel(&make_recipes(&parse_reactions(s)), ore_needed);
-            let upper = ore_needed_for_fuel(&make_recipes(&parse_reactions(s)), ore_needed+1);
+            let lower = ore_needed_for_fuel(&make_recipes(&parse_reactions(s).unwrap()), ore_needed);
+            let upper = ore_needed_for_fuel(&make_recipes(&parse_reactions(s).unwrap()), ore_needed+1);
             let success = lower <= TRILLION && upper > TRILLION;
             assert!(success, "Expected {} - {} to surround 1 trillion", lower, upper);
         }
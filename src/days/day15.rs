@@ -1,41 +1,67 @@
 use crate::error::Error;
-use crate::support::intcode::{ parse_intcode_ops };
-use self::droid::{ Droid, Outcome, Status, Direction };
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::{ Answer, Picture, Point, Direction, SparseGrid };
+use crate::support::render::{ render, show, BLACK, DARK_GREY, LIGHT_GREY, WHITE };
+use crate::support::animate;
+use self::droid::{ Droid, Outcome, Status };
 use std::collections::{ HashMap, HashSet, VecDeque };
-use std::io::Write;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let ops = parse_intcode_ops(input)?;
+pub struct Puzzle;
 
-    // Build a map of the area:
-    let droid = Droid::new(ops);
-    let map = build_map(droid)?;
+impl Solution for Puzzle {
+    const DAY: usize = 15;
+    const TITLE: &'static str = "Oxygen System";
+    const DRAWS_PICTURES: bool = true;
+    const ANIMATES: bool = true;
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let map = explore(input, pokes)?;
     print_map(&map)?;
 
     // Find the oxygen station and calculate the distance from droid to it:
-    let station_coords = map
-        .iter()
-        .find(|&(_,&f)| f == Feature::OxygenStation)
-        .map(|(&xy,_)| xy)
-        .unwrap();
-    println!("Star 1: {}", route_between((0,0), station_coords, &map).count());
+    let station_coords = find_station(&map)?;
+    let steps = route_between(Point::ORIGIN, station_coords, &map).count();
+    Ok(steps.into())
+}
+
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let map = explore(input, pokes)?;
 
     // Calculate max distance between station and furthest reachable map pos:
+    let station_coords = find_station(&map)?;
     let dist = all_surrounding(station_coords, &map)
         .map(|(d,_)| d)
         .max()
-        .unwrap();
-    println!("Star 2: {}", dist);
+        .ok_or_else(|| Error::no_solution("There's nowhere for the oxygen to spread to"))?;
+    Ok(dist.into())
+}
 
-    Ok(())
+/// Build a map of the area by driving the droid around.
+fn explore(input: &str, pokes: &Pokes) -> Result<Map,Error> {
+    let droid = Droid::new(pokes.machine(input)?);
+    build_map(droid)
+}
+
+fn find_station(map: &Map) -> Result<Point,Error> {
+    map.iter()
+        .find(|&(_,&f)| f == Feature::OxygenStation)
+        .map(|(p,_)| p)
+        .ok_or_else(|| Error::no_solution("The droid never found the oxygen station"))
 }
 
 /// Build up a map of the area by moving the droid to all unknown coords
 /// until there are none that are accessible.
-fn build_map(mut droid: Droid) -> Result<HashMap<(i64,i64), Feature>,Error> {
-    let mut map = HashMap::new();
-    let mut coords = (0,0);
-    let mut direction = Direction::North;
+fn build_map(mut droid: Droid) -> Result<Map,Error> {
+    let mut map = SparseGrid::new();
+    let mut coords = Point::ORIGIN;
+    let mut direction = Direction::Up;
     map.insert(coords, Feature::Empty);
     while let Some(outcome) = droid.step()? {
         match outcome {
@@ -50,61 +76,67 @@ fn build_map(mut droid: Droid) -> Result<HashMap<(i64,i64), Feature>,Error> {
             Outcome::Status(s) => {
                 match s {
                     Status::Moved { found_oxygen } => {
-                        coords = next_coords(coords, direction);
+                        coords = coords.step(direction);
                         let f = if found_oxygen { Feature::OxygenStation } else { Feature::Empty };
                         map.insert(coords, f);
+                        animate::frame(|| draw_map(&map, coords))?;
                     },
                     Status::HitWall => {
-                        let wall_coords = next_coords(coords, direction);
+                        let wall_coords = coords.step(direction);
                         map.insert(wall_coords, Feature::Wall);
+                        animate::frame(|| draw_map(&map, coords))?;
                     }
                 }
             }
         }
     }
-    Err(err!("Unexpected program end"))
+    Err(Error::intcode("Unexpected program end"))
 }
 
-/// Print the map
-fn print_map(map: &HashMap<(i64,i64), Feature>) -> Result<(),Error> {
-    let [(x1,y1),(x2,y2)] = map.keys().fold([(0,0),(0,0)], |[(x1,y1),(x2,y2)], &(x,y)| {
-        [(x1.min(x), y1.min(y)), (x2.max(x), y2.max(y))]
-    });
-    let stdout = std::io::stdout();
-    let mut stdout = stdout.lock();
-    for y in y1..=y2 {
-        for x in x1..=x2 {
-            match map.get(&(x,y)) {
-                None => stdout.write_all(b" ")?,
-                Some(Feature::Empty) => stdout.write_all(if (x,y) == (0,0) { b"d" } else { b"." })?,
-                Some(Feature::Wall) => stdout.write_all(b"#")?,
-                Some(Feature::OxygenStation) => stdout.write_all(b"o")?,
-            };
-        }
-        stdout.write_all(b"\n")?;
+/// Display the map on stderr, so that it doesn't get mixed up with the answers.
+fn print_map(map: &Map) -> Result<(),Error> {
+    show(&draw_map(map, Point::ORIGIN))?;
+    if let Some(bbox) = map.bounding_box() {
+        render(&Picture::of_box(bbox, |p| match map.get(p) {
+            None => LIGHT_GREY,
+            Some(Feature::Empty) => WHITE,
+            Some(Feature::Wall) => BLACK,
+            Some(Feature::OxygenStation) => DARK_GREY
+        }))?;
     }
     Ok(())
 }
 
+/// The map as text, with the droid ('d') on it.
+fn draw_map(map: &Map, droid: Point) -> String {
+    map.render(|p, f| match f {
+        _ if p == droid => 'd',
+        None => ' ',
+        Some(Feature::Empty) => '.',
+        Some(Feature::Wall) => '#',
+        Some(Feature::OxygenStation) => 'o'
+    })
+}
+
 /// The heart of the movement code; decide which square to try to move to next.
 /// return None to not move the droid at all (ie we've seen everything we care about).
-fn pick_direction(coords: (i64,i64), map: &HashMap<(i64,i64), Feature>) -> Option<Direction> {
+fn pick_direction(coords: Point, map: &Map) -> Option<Direction> {
     find_closest_unseen_coords(coords, map)
         .and_then(|end| route_between(coords, end, map).next())
 }
 
 /// Find the nearest unseen coords, None if no such coords are reachable.
-fn find_closest_unseen_coords(coords: (i64,i64), map: &HashMap<(i64,i64), Feature>) -> Option<(i64,i64)> {
+fn find_closest_unseen_coords(coords: Point, map: &Map) -> Option<Point> {
     all_surrounding(coords, map)
-        .filter(|(_,c)| !map.contains_key(c))
+        .filter(|&(_,c)| !map.contains(c))
         .map(|(_,c)| c)
         .next()
 }
 
 /// Find a route from one set of coords to another via known map.
-fn route_between(start: (i64,i64), end: (i64,i64), map: &HashMap<(i64,i64),Feature>) -> impl Iterator<Item=Direction> {
+fn route_between(start: Point, end: Point, map: &Map) -> impl Iterator<Item=Direction> {
     let mut curr = start;
-    let distance_from_end: HashMap<(i64,i64),usize> = all_surrounding(end, map)
+    let distance_from_end: HashMap<Point,usize> = all_surrounding(end, map)
         .take_while(|&(_,c)| c != start)
         .map(|(d,c)| (c,d))
         .chain([(end,0)].into_iter().cloned())
@@ -113,11 +145,11 @@ fn route_between(start: (i64,i64), end: (i64,i64), map: &HashMap<(i64,i64),Featu
         if curr == end {
             None
         } else {
-            let d = *[Direction::North, Direction::East, Direction::South, Direction::West]
-                .into_iter()
-                .min_by_key(|&&d| *distance_from_end.get(&next_coords(curr, d)).unwrap_or(&std::usize::MAX))
+            let d = *Direction::ALL
+                .iter()
+                .min_by_key(|&&d| *distance_from_end.get(&curr.step(d)).unwrap_or(&std::usize::MAX))
                 .unwrap();
-            curr = next_coords(curr, d);
+            curr = curr.step(d);
             Some(d)
         }
     })
@@ -125,19 +157,19 @@ fn route_between(start: (i64,i64), end: (i64,i64), map: &HashMap<(i64,i64),Featu
 
 /// An iterator over all coords from those provided, ordered by distance, and taking
 /// into account any walls that we know about in our map.
-fn all_surrounding<'a>(coords: (i64,i64), map: &'a HashMap<(i64,i64),Feature>) -> impl Iterator<Item=(usize,(i64,i64))> + 'a {
-    let mut tried: HashSet<(i64,i64)> = HashSet::new();
-    let mut next: VecDeque<(usize,(i64,i64))> = VecDeque::new();
+fn all_surrounding(coords: Point, map: &Map) -> impl Iterator<Item=(usize,Point)> + '_ {
+    let mut tried: HashSet<Point> = HashSet::new();
+    let mut next: VecDeque<(usize,Point)> = VecDeque::new();
     next.push_back((0,coords));
     tried.insert(coords);
     std::iter::from_fn(move || {
         let (next_distance, next_coords)
             = if let Some(c) = next.pop_front() { c } else { return None };
-        for c in surrounding(next_coords) {
+        for c in next_coords.neighbours() {
             if tried.contains(&c) {
                 continue
             }
-            if map.get(&c).unwrap_or(&Feature::Empty) == &Feature::Wall {
+            if map.get(c).unwrap_or(&Feature::Empty) == &Feature::Wall {
                 continue
             }
             next.push_back((next_distance+1,c));
@@ -147,21 +179,7 @@ fn all_surrounding<'a>(coords: (i64,i64), map: &'a HashMap<(i64,i64),Feature>) -
     })
 }
 
-/// Give back an iterator of the coords directly touching those provided.
-fn surrounding((x,y): (i64,i64)) -> impl Iterator<Item=(i64,i64)> {
-    static DIFFS: [(i64,i64);4] = [(0,-1), (1,0), (0,1), (-1,0)];
-    DIFFS.into_iter().map(move |(xd,yd)| (x+xd,y+yd))
-}
-
-/// Use a direction to transform some coords into new coords based on it.
-fn next_coords((x,y): (i64,i64), d: Direction) -> (i64,i64) {
-    match d {
-        Direction::North => (x,y-1),
-        Direction::South => (x,y+1),
-        Direction::East => (x+1,y),
-        Direction::West => (x-1,y)
-    }
-}
+type Map = SparseGrid<Feature>;
 
 #[derive(Debug,Clone,Copy,PartialEq,Eq)]
 enum Feature {
@@ -175,6 +193,7 @@ enum Feature {
 mod droid {
 
     use crate::error::Error;
+    use crate::support::Direction;
     use crate::support::intcode::{
         Intcode,
         Outcome as IntcodeOutcome,
@@ -187,8 +206,8 @@ mod droid {
     }
 
     impl Droid {
-        pub fn new(ops: Vec<i64>) -> Droid {
-            Droid { intcode: Intcode::new(ops) }
+        pub fn new(intcode: Intcode) -> Droid {
+            Droid { intcode }
         }
         pub fn try_move(&mut self, value: ProvideInputValue) -> Result<(),Error> {
             self.intcode.provide_input(value.0)
@@ -219,11 +238,6 @@ mod droid {
         Status(Status)
     }
 
-    #[derive(Debug,Clone,Copy)]
-    pub enum Direction {
-        North = 1, South = 2, West = 3, East = 4
-    }
-
     #[derive(Debug,Clone,Copy)]
     pub enum Status {
         Moved { found_oxygen: bool },
@@ -234,8 +248,15 @@ mod droid {
     pub struct ProvideInputValue(IntcodeProvideInputValue);
 
     impl ProvideInput {
+        /// The droid is told to move north (up) with 1, south with 2,
+        /// west with 3 and east with 4.
         pub fn value(self, d: Direction) -> ProvideInputValue {
-            ProvideInputValue(self.0.value(d as i64))
+            ProvideInputValue(self.0.value(match d {
+                Direction::Up => 1,
+                Direction::Down => 2,
+                Direction::Left => 3,
+                Direction::Right => 4
+            }))
         }
     }
 
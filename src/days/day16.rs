@@ -1,18 +1,41 @@
 use crate::error::Error;
+use crate::days::{ Solution, Stars, NoArgs };
+use crate::support::Answer;
+use crate::support::parse::single_line;
 use std::iter;
 use std::ops::Range;
 use rayon::prelude::*;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let vals = parse_input(input);
+pub struct Puzzle;
 
-    println!("Star 1: {}", stringify(&phases(vals.clone(), 100), 0, 8));
+impl Solution for Puzzle {
+    const DAY: usize = 16;
+    const TITLE: &'static str = "Flawed Frequency Transmission";
+    type Args = NoArgs;
+    fn solve(input: &str, _: NoArgs, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
+}
 
-    let more_vals = vals.repeat(10_000);
-    let skip: usize = stringify(&more_vals, 0, 7).parse().unwrap();
-    println!("Star 2: {}", stringify(&phases(more_vals, 100), skip, 8));
+pub fn part1(input: &str) -> Result<Answer,Error> {
+    let vals = parse_input(input)?;
+
+    let star1 = stringify(&phases(vals, 100), 0, 8);
+    Ok(star1.into())
+}
+
+pub fn part2(input: &str) -> Result<Answer,Error> {
+    let vals = parse_input(input)?;
 
-    Ok(())
+    let more_vals = vals.repeat(10_000);
+    let skip: usize = stringify(&more_vals, 0, 7).parse()?;
+    if skip + 8 > more_vals.len() {
+        return Err(Error::no_solution(format!("The message offset {} is past the end of the signal", skip)))
+    }
+    let star2 = stringify(&phases(more_vals, 100), skip, 8);
+    Ok(star2.into())
 }
 
 fn stringify(input: &[i8], offset: usize, limit: usize) -> String {
@@ -26,7 +49,8 @@ fn phases(mut input: Vec<i8>, n: usize) -> Vec<i8> {
     input
 }
 
-fn phase(input: &[i8]) -> Vec<i8> {
+/// Run one phase of FFT over the signal.
+pub fn phase(input: &[i8]) -> Vec<i8> {
     let rolling_sums: Vec<i32> = rolling_sum_iter(input).collect();
     let len = input.len();
     (0..input.len()).into_par_iter().map(move |n| {
@@ -66,8 +90,10 @@ fn repeating_digit_ranges(n: usize, offset: usize, len: usize) -> impl Iterator<
     })
 }
 
-fn parse_input(input: &str) -> Vec<i8> {
-    input.trim().bytes().map(|b| (b - 48) as i8).collect()
+/// The digits of the signal.
+pub fn parse_input(input: &str) -> Result<Vec<i8>,Error> {
+    let digits = single_line(input)?.digits(9)?;
+    Ok(digits.into_iter().map(|d| d as i8).collect())
 }
 
 type Indexes = Range<usize>;
\ No newline at end of file
@@ -1,50 +1,68 @@
 use crate::error::Error;
-use crate::support::intcode::{ Intcode, Outcome, parse_intcode_ops };
-use crate::support::digits;
-use std::collections::HashMap;
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::intcode::{ Intcode, Outcome, Poke, parse_intcode_ops };
+use crate::support::{ digits, Answer, Picture, Point, Direction, SparseGrid, AsciiMap, Whitespace };
+use crate::support::render::{ render, BLACK, DARK_GREY, WHITE };
 use std::iter::once;
-use self::Direction::*;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 17;
+    const TITLE: &'static str = "Set and Forget";
+    const DRAWS_PICTURES: bool = true;
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
     let ops = parse_intcode_ops(input)?;
-    let map = draw_map(ops.clone())?;
+    let map = draw_map(ops, pokes)?;
+    if let Some(bbox) = map.bounding_box() {
+        render(&Picture::of_box(bbox, |p| match map.get(p) {
+            Some(Feature::Scaffold) => BLACK,
+            Some(Feature::Robot(_)) => DARK_GREY,
+            Some(Feature::Space) | None => WHITE
+        }))?;
+    }
 
-    let alignments: i64 = find_intersections(&map).map(|(x,y)| x*y).sum();
-    println!("Star 1: {}", alignments);
+    let alignments: i64 = find_intersections(&map).map(|p| p.x * p.y).sum();
+    Ok(alignments.into())
+}
 
-    let mut ops = ops;
-    ops[0] = 2;
-    let commands = generate_robot_commands(&map);
-    let dust_collected = run_commands(ops, replace_all_commands(&commands)?)?;
-    println!("Star 2: {}", dust_collected);
+/// Any pokes are made to the robot once it's woken up, rather than
+/// to the camera that we draw the map with first.
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    let ops = parse_intcode_ops(input)?;
+    let map = draw_map(ops.clone(), &Pokes::default())?;
 
-    Ok(())
+    let commands = generate_robot_commands(&map)?;
+    let dust_collected = run_commands(ops, pokes, replace_all_commands(&commands)?)?;
+    Ok(dust_collected.into())
 }
 
 /// Draw the map we'll be traversing.
-fn draw_map(ops: Vec<i64>) -> Result<Map,Error> {
-    let mut map = HashMap::new();
+fn draw_map(ops: Vec<i64>, pokes: &Pokes) -> Result<Map,Error> {
+    let mut drawn = String::new();
     let mut intcode = Intcode::new(ops);
-    let mut x = 0;
-    let mut y = 0;
+    pokes.apply(&mut intcode)?;
     while let Some(outcome) = intcode.step()? {
         match outcome {
             Outcome::Output(val) => {
-                let val = val as u8;
-                if val == b'\n' {
-                    y += 1;
-                    x = 0;
-                } else {
-                    map.insert((x,y), Feature::from_u8(val));
-                    x += 1;
-                }
+                drawn.push(val as u8 as char);
             },
             Outcome::NeedsInput(_) => {
-                return Err(err!("Intcode program asked for input, which shouldn't happen"))
+                return Err(Error::intcode("Intcode program asked for input, which shouldn't happen"))
             }
         }
     }
-    Ok(Map::new(map))
+    let map = AsciiMap::parse(&drawn, Whitespace::Keep, Feature::from_char)
+        .map_err(|e| Error::intcode(format!("The camera drew an unexpected map: {}", e)))?;
+    Ok(Map::new(map.into_grid()))
 }
 
 /// Given slightly modified ops, this feeds in the movement commands and such
@@ -52,9 +70,12 @@ fn draw_map(ops: Vec<i64>) -> Result<Map,Error> {
 /// and returns the final value given back.
 fn run_commands(
     ops: Vec<i64>,
+    pokes: &Pokes,
     cmds: Replacements
 ) -> Result<i64,Error> {
-    let mut intcode = Intcode::new(ops);
+    // Wake the robot up before it runs, so that it moves:
+    let mut intcode = Intcode::with_pokes(ops, &[Poke::new(0, 2)]);
+    pokes.apply(&mut intcode)?;
     let mut last_output = 0;
     let mut input = Command::to_ascii(&cmds.main)
         .chain(once(b'\n'))
@@ -72,7 +93,7 @@ fn run_commands(
                 if let Some(c) = input.next() {
                     intcode.provide_input(p.value(c as i64))?;
                 } else {
-                    return Err(err!("No more input to provide but input asked for"))
+                    return Err(Error::intcode("No more input to provide but input asked for"))
                 }
             }
             Outcome::Output(i) => {
@@ -84,21 +105,17 @@ fn run_commands(
 }
 
 /// Where does the scaffolding cross?
-fn find_intersections(map: &Map) -> impl Iterator<Item=(i64,i64)> + '_ {
-    fn surrounded_with_scaffold(coords: (i64,i64), map: &Map) -> bool {
-        map.is_scaffold_at(coords) && surrounding(coords).all(|c| map.is_scaffold_at(c))
-    }
-    fn surrounding((x,y): (i64,i64)) -> impl Iterator<Item=(i64,i64)> {
-        static DIFFS: [(i64,i64);4] = [(0,-1), (1,0), (0,1), (-1,0)];
-        DIFFS.into_iter().map(move |(xd,yd)| (x+xd,y+yd))
+fn find_intersections(map: &Map) -> impl Iterator<Item=Point> + '_ {
+    fn surrounded_with_scaffold(coords: Point, map: &Map) -> bool {
+        map.is_scaffold_at(coords) && coords.neighbours().all(|c| map.is_scaffold_at(c))
     }
-    map.keys().filter(move |&&c| surrounded_with_scaffold(c,map)).map(|&c| c)
+    map.points().filter(move |&c| surrounded_with_scaffold(c,map))
 }
 
 /// Have the robot traverse the map from its current position until it leaves
 /// the map, and return the list of commands required for it to do so.
-fn generate_robot_commands(map: &Map) -> Vec<Command> {
-    let (mut coords, mut direction) = find_robot(map);
+fn generate_robot_commands(map: &Map) -> Result<Vec<Command>,Error> {
+    let (mut coords, mut direction) = find_robot(map)?;
     let mut commands = vec![];
     let mut forward_steps = 0;
     let commit_forward = |commands: &mut Vec<_>, forward_steps: &mut _| {
@@ -108,20 +125,20 @@ fn generate_robot_commands(map: &Map) -> Vec<Command> {
         *forward_steps = 0;
     };
     loop {
-        if map.is_scaffold_at(direction.step_coords(coords)) {
+        if map.is_scaffold_at(coords.step(direction)) {
             forward_steps += 1;
-            coords = direction.step_coords(coords)
-        } else if map.is_scaffold_at(direction.left().step_coords(coords)) {
+            coords = coords.step(direction)
+        } else if map.is_scaffold_at(coords.step(direction.left())) {
             commit_forward(&mut commands, &mut forward_steps);
             commands.push(Command::Left);
             direction = direction.left();
-        } else if map.is_scaffold_at(direction.right().step_coords(coords)) {
+        } else if map.is_scaffold_at(coords.step(direction.right())) {
             commit_forward(&mut commands, &mut forward_steps);
             commands.push(Command::Right);
             direction = direction.right();
         } else {
             commit_forward(&mut commands, &mut forward_steps);
-            return commands
+            return Ok(commands)
         }
     }
 }
@@ -187,7 +204,7 @@ fn replace_all_commands(commands: &[Command]) -> Result<Replacements,Error> {
             }
         }
     }
-    Err(err!("No suitable combinations found"))
+    Err(Error::no_solution("No suitable combinations found"))
 }
 
 struct Replacements {
@@ -221,11 +238,10 @@ fn possible_replacements(commands: &[Command], replace_with: Command) -> impl It
 }
 
 /// Find the robot on the map
-fn find_robot(map: &Map) -> ((i64,i64), Direction) {
+fn find_robot(map: &Map) -> Result<(Point, Direction),Error> {
     map.iter()
-        .filter_map(|(&c,&f)| if let Feature::Robot(d) = f { Some((c,d)) } else { None })
-        .next()
-        .unwrap()
+        .find_map(|(c,&f)| if let Feature::Robot(d) = f { Some((c,d)) } else { None })
+        .ok_or_else(|| Error::intcode("The camera drew a map without the robot on it"))
 }
 
 /// A Map can contain these features
@@ -237,50 +253,15 @@ enum Feature {
 }
 
 impl Feature {
-    fn from_u8(c: u8) -> Feature {
+    fn from_char(c: char) -> Option<Feature> {
         match c {
-            b'#'  => Feature::Scaffold,
-            b'^'  => Feature::Robot(Direction::North),
-            b'>'  => Feature::Robot(Direction::East),
-            b'v'  => Feature::Robot(Direction::South),
-            b'<'  => Feature::Robot(Direction::West),
-            _     => Feature::Space
-        }
-    }
-}
-
-/// The robot is facing one of these directions
-#[derive(Clone,Copy,Debug,PartialEq,Eq)]
-enum Direction {
-    North,
-    South,
-    East,
-    West
-}
-
-impl Direction {
-    fn left(self) -> Direction {
-        match self {
-            North => West,
-            West  => South,
-            South => East,
-            East  => North
-        }
-    }
-    fn right(self) -> Direction {
-        match self {
-            North => East,
-            East  => South,
-            South => West,
-            West  => North
-        }
-    }
-    fn step_coords(self, (x,y): (i64,i64)) -> (i64,i64) {
-        match self {
-            North => (x,y-1),
-            South => (x,y+1),
-            East  => (x+1,y),
-            West  => (x-1,y),
+            '#' => Some(Feature::Scaffold),
+            '^' => Some(Feature::Robot(Direction::Up)),
+            '>' => Some(Feature::Robot(Direction::Right)),
+            'v' => Some(Feature::Robot(Direction::Down)),
+            '<' => Some(Feature::Robot(Direction::Left)),
+            '.' => Some(Feature::Space),
+            _   => None
         }
     }
 }
@@ -335,15 +316,15 @@ impl Command {
 /// for oft asked-for queries (but otherwise looking like a HashMap).
 #[derive(Clone,Debug)]
 struct Map {
-    map: HashMap<(i64,i64), Feature>
+    map: SparseGrid<Feature>
 }
 
 impl Map {
-    fn new(map: HashMap<(i64,i64), Feature>) -> Map {
+    fn new(map: SparseGrid<Feature>) -> Map {
         Map { map }
     }
-    fn is_scaffold_at(&self, coords: (i64,i64)) -> bool {
-        match self.get(&coords).unwrap_or(&Feature::Space) {
+    fn is_scaffold_at(&self, coords: Point) -> bool {
+        match self.get(coords).unwrap_or(&Feature::Space) {
             Feature::Scaffold => true,
             Feature::Robot(_) => true,
             Feature::Space => false
@@ -352,7 +333,7 @@ impl Map {
 }
 
 impl std::ops::Deref for Map {
-    type Target = HashMap<(i64,i64), Feature>;
+    type Target = SparseGrid<Feature>;
     fn deref(&self) -> &Self::Target {
         &self.map
     }
@@ -1,40 +1,66 @@
 use std::collections::{ HashSet, HashMap, VecDeque };
+use std::str::FromStr;
 use pathfinding::prelude::fringe;
 use crate::error::Error;
+use crate::days::{ Solution, Stars, NoArgs };
+use crate::support::{ Answer, Progress };
 use self::map::{ Map, Feature };
 use self::keys::{ Keys };
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let map = Map::from_str(input);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 18;
+    const TITLE: &'static str = "Many-Worlds Interpretation";
+    type Args = NoArgs;
+    fn solve(input: &str, _: NoArgs, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
+}
+
+/// There's one start. Work out the best route to get all of the keys.
+pub fn part1(input: &str) -> Result<Answer,Error> {
+    let map = Map::from_str(input)?;
     let n_keys = map.count_keys();
 
-    // Star 1: There's one start. Work out the best route to get
-    // all of the keys..
-    {
+    let star1 = {
         let graph = build_graph(&map);
+        let mut progress = Progress::new("Day 18 part 1", "states expanded");
         let best_single_route = fringe(
             &(Feature::Start(0), Keys::new()),
             // Reachable locations from here:
-            |&(f,k)| possible_moves(f,k,&graph).map(|(f,k,c)| ((f,k),c)),
+            |&(f,k)| {
+                progress.tick();
+                possible_moves(f,k,&graph).map(|(f,k,c)| ((f,k),c))
+            },
             // Min possible distance from end:
             |(_,k)| n_keys - k.len(),
             // When are we done?
             |(_,k)| k.len() == n_keys
         );
-        println!("Star 1: {:?}", best_single_route.unwrap().1);
-    }
+        best_single_route.ok_or_else(|| Error::no_solution("Cannot collect all of the keys"))?.1
+    };
+    Ok(star1.into())
+}
 
-    // Star 2: There are 4 starts. Again, work out the best route
-    // each robot can take from a start to pick up all of the keys.
-    // This takes a couple of minutes to solve (wheras the above takes
-    // a small numebr of ms..)
-    {
+/// There are 4 starts. Again, work out the best route each robot can
+/// take from a start to pick up all of the keys. This takes a couple of
+/// minutes to solve (wheras the above takes a small numebr of ms..)
+pub fn part2(input: &str) -> Result<Answer,Error> {
+    let map = Map::from_str(input)?;
+    let n_keys = map.count_keys();
+
+    let star2 = {
         let map = map.make_4_starts();
         let graph = build_graph(&map);
+        let mut progress = Progress::new("Day 18 part 2", "states expanded");
         let best_multi_route = fringe(
             &([Feature::Start(0),Feature::Start(1),Feature::Start(2),Feature::Start(3)], Keys::new()),
             // Reachable locations from here:
             |&([f1,f2,f3,f4],k)| {
+                progress.tick();
                 let f1m = possible_moves(f1, k, &graph).map(move |(f,k,d)| (([f,f2,f3,f4],k),d));
                 let f2m = possible_moves(f2, k, &graph).map(move |(f,k,d)| (([f1,f,f3,f4],k),d));
                 let f3m = possible_moves(f3, k, &graph).map(move |(f,k,d)| (([f1,f2,f,f4],k),d));
@@ -46,17 +72,16 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
             // When are we done?
             |(_,k)| k.len() == n_keys
         );
-        println!("Star 2: {:?}", best_multi_route.unwrap().1);
-    }
-
-    Ok(())
+        best_multi_route.ok_or_else(|| Error::no_solution("Cannot collect all of the keys with 4 robots"))?.1
+    };
+    Ok(star2.into())
 }
 
 // For a given feature and set of Keys, what are the possible resulting features, keys and cost of each move
 fn possible_moves(feature: Feature, keys: Keys, graph: &Graph) -> impl Iterator<Item=(Feature,Keys,usize)> + '_ {
     graph.get(&feature)
-        .unwrap()
         .into_iter()
+        .flatten()
         .filter(move |(f,_)| {
             match f {
                 Feature::Door(d) => keys.contains(*d),
@@ -109,7 +134,7 @@ fn step<'a>((x,y): (i16,i16), map: &'a Map) -> impl Iterator<Item=(i16,i16)> + '
         .map(move |&(xd,yd)| (x+xd,y+yd))
         .filter(move |&(x,y)| {
             match map.get((x,y)) {
-                Feature::Key(_) | Feature::Door(_) | Feature::Empty => true,
+                Feature::Key(_) | Feature::Door(_) | Feature::Empty | Feature::Start(_) => true,
                 _ => false
             }
         })
@@ -144,7 +169,7 @@ pub mod keys {
         }
     }
 
-    #[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+    #[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default)]
     pub struct Keys(u32);
 
     impl Keys {
@@ -154,6 +179,9 @@ pub mod keys {
         pub fn len(&self) -> usize {
             self.0.count_ones() as usize
         }
+        pub fn is_empty(&self) -> bool {
+            self.0 == 0
+        }
         pub fn with(self, key: Key) -> Keys {
             Keys(self.0 | key.mask())
         }
@@ -167,6 +195,9 @@ pub mod keys {
 /// A map of the area.
 pub mod map {
     use std::collections::HashMap;
+    use std::str::FromStr;
+    use crate::error::Error;
+    use crate::support::{ AsciiMap, Whitespace };
     use super::keys::Key;
 
     #[derive(Debug,Clone)]
@@ -174,22 +205,26 @@ pub mod map {
         inner: HashMap<(i16,i16), Feature>
     }
 
-    impl Map {
-        pub fn from_str(input: &str) -> Map {
-            let inner = input.trim().lines().enumerate().flat_map(|(y, line)| {
-                line.trim().chars().enumerate().map(move |(x, c)| {
-                    let f = match c {
-                        '#' => Feature::Wall,
-                        'A'..='Z' => Feature::Door(Key::from_char(c)),
-                        'a'..='z' => Feature::Key(Key::from_char(c)),
-                        '@' => Feature::Start(0),
-                        _ => Feature::Empty,
-                    };
-                    ((x as i16,y as i16), f)
-                })
-            }).collect();
-            Map { inner }
+    impl FromStr for Map {
+        type Err = Error;
+        fn from_str(input: &str) -> Result<Map,Error> {
+            let map = AsciiMap::parse(input, Whitespace::Strip, |c| match c {
+                '#' => Some(Feature::Wall),
+                'A'..='Z' => Some(Feature::Door(Key::from_char(c))),
+                'a'..='z' => Some(Feature::Key(Key::from_char(c))),
+                '@' => Some(Feature::Start(0)),
+                '.' => Some(Feature::Empty),
+                _ => None
+            })?;
+            let inner: HashMap<_,_> = map.iter().map(|(p,&f)| ((p.x as i16, p.y as i16), f)).collect();
+            if !inner.values().any(|f| f.is_start()) {
+                return Err(Error::parse_somewhere("Expected an '@' to start from in the map but there isn't one"))
+            }
+            Ok(Map { inner })
         }
+    }
+
+    impl Map {
         pub fn iter(&self) -> impl Iterator<Item=((i16,i16),Feature)> + '_ {
             self.inner.iter().map(|(&d,&f)| (d,f))
         }
@@ -203,7 +238,7 @@ pub mod map {
         }
         pub fn make_4_starts(self) -> Map {
             let mut inner = self.inner;
-            let (x,y) = *inner.iter().find(|(_,f)| f.is_start()).unwrap().0;
+            let (x,y) = *inner.iter().find(|(_,f)| f.is_start()).expect("maps are parsed with a start").0;
             inner.insert((x,y), Feature::Wall);
             inner.insert((x-1,y), Feature::Wall);
             inner.insert((x+1,y), Feature::Wall);
@@ -1,69 +1,79 @@
 use crate::error::Error;
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::{ Answer, Picture, Point, DenseGrid };
+use crate::support::render::{ render, show, BLACK, WHITE };
 use self::drones::Drones;
-use std::io::Write;
-
-pub fn both_parts(input: &str) -> Result<(), Error> {
-
-    let drones = Drones::new(input)?;
-
-    // First, display the beam and count pulled points:
-    let mut pulled_points = 0;
-    {
-        let stdout = std::io::stdout();
-        let mut stdout = stdout.lock();
-        for y in 0..50 {
-            for x in 0..50 {
-                if drones.is_pulled_at(x,y)? {
-                    pulled_points += 1;
-                    stdout.write_all(b"#")?;
-                } else {
-                    stdout.write_all(b".")?;
-                }
-            }
-            stdout.write_all(b"\n")?;
-        }
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 19;
+    const TITLE: &'static str = "Tractor Beam";
+    const DRAWS_PICTURES: bool = true;
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+/// Any pokes are made to the program that each drone runs.
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+
+    let drones = Drones::new(pokes.machine(input)?);
+
+    // Scan the area closest to the emitter:
+    let mut beam = DenseGrid::new(50, 50, false);
+    for p in beam.bounding_box().points() {
+        beam.set(p, drones.is_pulled_at(p)?);
     }
-    println!("Star 1: {}", pulled_points);
 
-    // Next, work diagonally down and scan for a diagonal
+    // Display the beam (on stderr, out of the way of the
+    // answers) and count pulled points:
+    let drawn = beam.render(|_, &pulled| if pulled { '#' } else { '.' });
+    show(&drawn)?;
+    render(&Picture::of_box(beam.bounding_box(), |p| if beam.get(p) == Some(&true) { BLACK } else { WHITE }))?;
+    let pulled_points = beam.iter().filter(|&(_, &pulled)| pulled).count();
+    Ok(pulled_points.into())
+}
+
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+
+    let drones = Drones::new(pokes.machine(input)?);
+
+    // Work diagonally down and scan for a diagonal
     // large enough to host a 100x100 square.
-    let mut best_topright = (0,0);
-    let mut best_bottomleft = (0,0);
+    let up_right = Point::new(1,-1);
+    let mut best_topright = Point::ORIGIN;
+    let mut best_bottomleft = Point::ORIGIN;
     let mut best_manhatten = 0;
-    let mut xy = (49,49);
+    let mut xy = Point::new(49,49);
     while best_manhatten < 200 {
 
         // My beam is slightly above horizontal so adjust up to it:
-        while !drones.is_pulled_at(xy.0,xy.1)? {
-            xy = (xy.0+1,xy.1-1)
+        while !drones.is_pulled_at(xy)? {
+            xy = xy + up_right;
         }
 
         // Now keep going up until we leave the beam again:
         let mut topright = xy;
-        while drones.is_pulled_at(topright.0+1,topright.1-1)? {
-            topright = (topright.0+1,topright.1-1);
+        while drones.is_pulled_at(topright + up_right)? {
+            topright = topright + up_right;
         }
 
-        let dist = manhatten(topright, xy) + 2;
+        let dist = topright.manhattan(xy) + 2;
         if dist > best_manhatten {
             best_manhatten = dist;
             best_topright = topright;
             best_bottomleft = xy;
         }
 
-        xy.0 += 1;
-        xy.1 += 1;
+        xy = xy + Point::new(1,1);
     }
-    let topleft = (best_bottomleft.0,best_topright.1);
-    println!("Star 2: {}", topleft.0 * 10_000 + topleft.1);
-
-    Ok(())
-}
+    let topleft = Point::new(best_bottomleft.x, best_topright.y);
 
-fn manhatten((x1,y1): (usize,usize), (x2,y2): (usize,usize)) -> usize {
-    let xd = if x1 < x2 { x2 - x1 } else { x1 - x2 };
-    let yd = if y1 < y2 { y2 - y1 } else { y1 - y2 };
-    xd + yd
+    Ok((topleft.x * 10_000 + topleft.y).into())
 }
 
 /// Our drones program.
@@ -71,33 +81,36 @@ mod drones {
 
     use std::iter;
     use crate::error::Error;
-    use crate::support::intcode::{ parse_intcode_ops, Intcode, Outcome };
+    use crate::support::Point;
+    use crate::support::intcode::{ Intcode, Outcome };
 
     pub struct Drones {
-        ops: Vec<i64>,
+        intcode: Intcode,
     }
     impl Drones {
-        pub fn new(input: &str) -> Result<Drones,Error> {
-            let ops = parse_intcode_ops(input)?;
-            Ok(Drones { ops })
+        /// Drones that each run a copy of this machine, which shouldn't have started yet.
+        pub fn new(intcode: Intcode) -> Drones {
+            Drones { intcode }
         }
-        pub fn is_pulled_at(&self, x: usize, y: usize) -> Result<bool,Error> {
-            let mut intcode = Intcode::new(self.ops.clone());
-            let mut input = iter::once(x).chain(iter::once(y));
+        pub fn is_pulled_at(&self, p: Point) -> Result<bool,Error> {
+            let mut intcode = self.intcode.clone();
+            let mut input = iter::once(p.x).chain(iter::once(p.y));
             loop {
                 if let Some(outcome) = intcode.step()? {
                     match outcome {
                         Outcome::NeedsInput(p) => {
                             if let Some(i) = input.next() {
-                                intcode.provide_input(p.value(i as i64))?;
+                                intcode.provide_input(p.value(i))?;
                             } else {
-                                return Err(err!("Unexpected input requirement"))
+                                return Err(Error::intcode("Unexpected input requirement"))
                             }
                         },
                         Outcome::Output(v) => {
                             return Ok(v != 0)
                         }
                     }
+                } else {
+                    return Err(Error::intcode("The drone finished without saying whether it was pulled"))
                 }
             }
         }
@@ -1,13 +1,28 @@
 use crate::error::Error;
+use crate::days::{ Solution, Stars, NoArgs };
+use crate::support::Answer;
 use self::map::{ Map };
 use pathfinding::prelude::dijkstra;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
-    let map = Map::from_str(input);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 20;
+    const TITLE: &'static str = "Donut Maze";
+    type Args = NoArgs;
+    fn solve(input: &str, _: NoArgs, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<Answer,Error> {
+    let map = Map::from_str(input)?;
     let start = map.find_start();
     let end = map.find_end();
 
-    {
+    let star1 = {
         let steps = dijkstra(
             &start,
             |&xy| map
@@ -15,10 +30,17 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
                 .map(|(xy,_)| (xy,1)),
             |&xy| xy == end
         );
-        println!("Star 1: {:?}", steps.map(|(_,n)| n).unwrap_or(0));
-    }
+        steps.ok_or_else(|| Error::no_solution("No route from AA to ZZ"))?.1
+    };
+    Ok(star1.into())
+}
 
-    {
+pub fn part2(input: &str) -> Result<Answer,Error> {
+    let map = Map::from_str(input)?;
+    let start = map.find_start();
+    let end = map.find_end();
+
+    let star2 = {
         let steps = dijkstra(
             &(start,0),
             |&(xy,level)| {
@@ -45,15 +67,16 @@ pub fn both_parts(input: &str) -> Result<(), Error> {
             // We only finish when we are back on level 0:
             |&(xy,level)| xy == end && level == 0
         );
-        println!("Star 2: {:?}", steps.map(|(_,n)| n).unwrap_or(0));
-    }
-
-    Ok(())
+        steps.ok_or_else(|| Error::no_solution("No route from AA to ZZ"))?.1
+    };
+    Ok(star2.into())
 }
 
 mod map {
 
     use std::collections::HashMap;
+    use crate::error::Error;
+    use crate::support::{ AsciiMap, Point, Whitespace };
 
     #[derive(Debug,Clone)]
     pub struct Map {
@@ -63,29 +86,36 @@ mod map {
         portal_locations: HashMap<(usize,usize),((usize,usize), [u8;2])>,
         // Size of the map
         width: usize,
-        height: usize
+        height: usize,
+        // Where we start and finish, next to the AA and ZZ portals
+        start: (usize,usize),
+        end: (usize,usize)
     }
 
     impl Map {
-        pub fn from_str(input: &str) -> Map {
-            // make an ascii map first so that we can access chars by coords.
-            let ascii_map: HashMap<(usize,usize),u8> = input
-                .lines()
-                .enumerate()
-                .flat_map(|(y,line)| line.chars().enumerate().map(move |(x,c)| ((x,y),c as u8)))
+        pub fn from_str(input: &str) -> Result<Map,Error> {
+            // make an ascii map first so that we can access chars by coords. Spaces
+            // matter here, as they line the portal labels up with the maze.
+            let ascii_map = AsciiMap::parse(input, Whitespace::Keep, |c| match c {
+                '#' | '.' | ' ' | 'A'..='Z' => Some(c as u8),
+                _ => None
+            })?;
+            let chars: HashMap<(usize,usize),u8> = ascii_map
+                .iter()
+                .map(|(p,&c)| ((p.x as usize, p.y as usize), c))
                 .collect();
 
             // Convert this into a proper feature based map with portals.
-            let map: HashMap<(usize,usize),Feature> = ascii_map.iter().map(|(&xy,&c)| {
+            let map: HashMap<(usize,usize),Feature> = chars.iter().map(|(&(x,y),&c)| {
                 let f = match c {
                     b'#' => Feature::Wall,
                     b'.' => Feature::Empty,
                     b'A' ..= b'Z' => {
-                        if surrounding_items(xy, &ascii_map).any(|b| b == b'.') {
-                            let other = surrounding_items(xy, &ascii_map)
-                                .filter(|b| (b'A'..=b'Z').contains(b))
-                                .next()
-                                .unwrap();
+                        if surrounding_items((x,y), &chars).any(|b| b == b'.') {
+                            let other = surrounding_items((x,y), &chars)
+                                .find(|b| (b'A'..=b'Z').contains(b))
+                                .ok_or_else(|| ascii_map.error_at(Point::new(x as i64, y as i64),
+                                    format!("Expected the portal label '{}' to have two letters", c as char)))?;
                             let pair = if other < c { [other,c] } else { [c,other] };
                             Feature::Portal(pair)
                         } else {
@@ -94,8 +124,8 @@ mod map {
                     }
                     _ => Feature::Void
                 };
-                (xy,f)
-            }).collect();
+                Ok(((x,y),f))
+            }).collect::<Result<_,Error>>()?;
 
             // Store all of the portal locations for easy transportation.
             let portal_locations: HashMap<(usize,usize),((usize,usize), [u8;2])> = map.iter()
@@ -124,7 +154,12 @@ mod map {
             // Get dimensions (this includes emptiness):
             let (width, height) = map.keys().fold((0,0), |(x1,y1),&(x2,y2)| (x1.max(x2),y1.max(y2)));
 
-            Map { inner: map, portal_locations, width, height }
+            let mut map = Map { inner: map, portal_locations, width, height, start: (0,0), end: (0,0) };
+            map.start = map.find_portal([b'A',b'A'])
+                .ok_or_else(|| Error::parse_somewhere("Expected an AA portal to start from but there isn't one"))?;
+            map.end = map.find_portal([b'Z',b'Z'])
+                .ok_or_else(|| Error::parse_somewhere("Expected a ZZ portal to finish at but there isn't one"))?;
+            Ok(map)
         }
         pub fn possible_moves(&self, pos: (usize,usize)) -> impl Iterator<Item=((usize,usize),bool)> + '_ {
             let normal_moves = surrounding(pos)
@@ -137,10 +172,10 @@ mod map {
             normal_moves.chain(portal_jump)
         }
         pub fn find_start(&self) -> (usize,usize) {
-            self.find_portal([b'A',b'A']).unwrap()
+            self.start
         }
         pub fn find_end(&self) -> (usize,usize) {
-            self.find_portal([b'Z',b'Z']).unwrap()
+            self.end
         }
         fn find_portal(&self, portal: [u8;2]) -> Option<(usize,usize)> {
             self.inner.iter().find(|&(&xy,&f)| {
@@ -152,7 +187,7 @@ mod map {
             }).map(|(&xy,_)| xy)
         }
         pub fn is_outer(&self, (x,y): (usize,usize)) -> bool {
-            x <= 2 || y <= 2 || x >= self.width - 2 || y >= self.height - 2
+            x <= 2 || y <= 2 || x + 2 >= self.width || y + 2 >= self.height
         }
     }
 
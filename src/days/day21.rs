@@ -1,54 +1,86 @@
+use structopt::StructOpt;
 use crate::error::Error;
-use crate::support::{ Terminal, IntoReadIter, Uppercase };
-use std::io::{ Read, sink, stdin, stdout };
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::{ Terminal, NonAscii, Console, Script, WriteString, Answer };
+use std::path::{ Path, PathBuf };
+use std::str::FromStr;
 
-pub fn both_parts(input: &str, interactive: bool) -> Result<(), Error> {
+pub struct Puzzle;
 
-    if interactive {
-        let mut terminal = Terminal::from_str(input, Uppercase::new(stdin()), stdout())?;
-        if let Some(n) = terminal.step()? {
-            println!("Result: {}", n);
-        } else {
-            println!("No result obtained");
-        }
-        return Ok(())
-    }
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    #[structopt(long, short, help = "Enable interactive springbot programming")]
+    interactive: bool,
+    #[structopt(long, parse(from_os_str), conflicts_with = "interactive", help = "Run the springbot program in this script file")]
+    script: Option<PathBuf>,
+    #[structopt(flatten)]
+    pokes: Pokes
+}
 
-    {
-        let mut terminal = Terminal::from_str(input, prepare_commands(PART1), sink())?;
-        if let Some(n) = terminal.step()? {
-            println!("Star 1: {}", n);
+impl Solution for Puzzle {
+    const DAY: usize = 21;
+    const TITLE: &'static str = "Springdroid Adventure";
+    type Args = Args;
+    fn solve(input: &str, args: Args, stars: &mut Stars) -> Result<(),Error> {
+        let pokes = &args.pokes;
+        if args.interactive {
+            stars.result(interactive(input, pokes)?);
+        } else if let Some(path) = &args.script {
+            stars.result(script(input, pokes, path)?);
         } else {
-            println!("Star 1 unsuccessful");
+            stars.part(1, || part1(input, pokes))?;
+            stars.part(2, || part2(input, pokes))?;
         }
+        Ok(())
     }
+}
 
-    {
-        let mut terminal = Terminal::from_str(input, prepare_commands(PART2), sink())?;
-        if let Some(n) = terminal.step()? {
-            println!("Star 2: {}", n);
-        } else {
-            println!("Star 2 unsuccessful");
-        }
-    }
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    hull_damage(input, pokes, Script::from_str(PART1)?)
+}
 
-    Ok(())
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+    hull_damage(input, pokes, Script::from_str(PART2)?)
 }
 
-pub fn prepare_commands(commands: &str) -> impl Read {
-    let mut out = vec![];
-    for line in commands.trim().lines() {
-        let s = if let Some(n) = line.find('#') {
-            line[0..n].trim()
-        } else {
-            line
-        };
-        if s.len() > 0 {
-            out.extend_from_slice(s.as_bytes());
-            out.push(b'\n');
-        }
+/// Run the springscript in some file rather than one of the built in ones.
+pub fn script(input: &str, pokes: &Pokes, path: &Path) -> Result<Answer, Error> {
+    hull_damage(input, pokes, Script::from_file(path)?)
+}
+
+/// Program the springdroid by hand.
+pub fn interactive(input: &str, pokes: &Pokes) -> Result<Answer, Error> {
+    let mut console = Console::new(pokes.machine(input)?)
+        .uppercase()
+        .non_ascii(NonAscii::Collect);
+    match console.run()?.last() {
+        Some(&n) => Ok(n.into()),
+        None => Err(Error::no_solution("No result obtained"))
     }
-    out.into_iter().into_reader()
+}
+
+/// The hull damage reported by a springscript, or an error showing
+/// where the springdroid fell.
+fn hull_damage(input: &str, pokes: &Pokes, script: Script) -> Result<Answer, Error> {
+    match run_script(input, pokes, script)? {
+        (Some(n), _) => Ok(n.into()),
+        (None, output) => Err(Error::no_solution(format!("The springdroid didn't make it across:\n{}", output)))
+    }
+}
+
+/// Run a springscript, handing back the hull damage reported if the
+/// springdroid makes it across, and the output (which shows where it
+/// fell if it doesn't).
+fn run_script(input: &str, pokes: &Pokes, script: Script) -> Result<(Option<i64>,String), Error> {
+    let mut output = WriteString::new();
+    let (reader, writer) = script.into_io(&mut output);
+    let result = Terminal::new(pokes.machine(input)?, reader.clone(), writer)
+        .non_ascii(NonAscii::Collect)
+        .run()?
+        .last()
+        .copied();
+    reader.finish()?;
+    Ok((result, output.into_string()))
 }
 
 static PART1: &str = "
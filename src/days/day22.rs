@@ -1,35 +1,55 @@
 use crate::error::Error;
-use once_cell::sync::Lazy;
-use regex::Regex;
+use structopt::StructOpt;
+use crate::days::{ Solution, Stars };
+use crate::support::Answer;
+use crate::support::parse::{ lines, Line };
 use num::{ BigInt, FromPrimitive, ToPrimitive };
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
+pub struct Puzzle;
+
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    #[structopt(long, default_value = "10007", help = "How many cards are in the deck")]
+    deck_size: usize,
+    #[structopt(long, default_value = "2019", help = "The card to find the position of after shuffling")]
+    card: usize
+}
+
+impl Solution for Puzzle {
+    const DAY: usize = 22;
+    const TITLE: &'static str = "Slam Shuffle";
+    type Args = Args;
+    fn solve(input: &str, Args { deck_size, card }: Args, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, deck_size, card))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str, deck_size: usize, card: usize) -> Result<Answer, Error> {
+    if card >= deck_size {
+        return Err(err!("Card {} isn't in a deck of {} cards", card, deck_size))
+    }
 
     let techniques = parse_input(input)?;
 
     // Part 1: transform the location by applying the shuffle techniques
     // one after the other to it. I'll need to reverse this for part 2 so
     // I'm factoring out the `%` step and using bg numbers to represent:
-    {
-        let mut loc: BigInt = 2019.into();
-        let len: BigInt = 10007.into();
-        for t in &techniques {
-            loc = t.apply_to_location_no_mod(&loc, &len);
-        }
-        println!("Star 1: {}", positive_mod(&loc,&len).to_usize().unwrap());
+    let mut loc: BigInt = card.into();
+    let len: BigInt = deck_size.into();
+    for t in &techniques {
+        loc = t.apply_to_location_no_mod(&loc, &len);
     }
 
     // Part 2: we need to implement `unapply_location_no_mod` in such a way
     // that we can avoid iterating it many times. Modular division may be key.
 
-    Ok(())
+    Ok(positive_mod(&loc,&len).to_usize().unwrap().into())
 }
 
 fn parse_input(input: &str) -> Result<Vec<Technique>,Error> {
-    input
-        .trim()
-        .lines()
-        .map(Technique::from_str)
+    lines(input)
+        .map(|line| Technique::from_line(&line))
         .collect()
 }
 
@@ -41,26 +61,19 @@ enum Technique {
 }
 
 impl Technique {
-    fn from_str(line: &str) -> Result<Technique,Error> {
-        static NUMBER: Lazy<Regex>
-            = Lazy::new(|| Regex::new(r"-?[0-9]+").unwrap());
-        let line = line.trim();
-        if line == "deal into new stack" {
+    fn from_line(line: &Line) -> Result<Technique,Error> {
+        let text = line.text;
+        if text == "deal into new stack" {
             Ok(Technique::DealIntoNewStack)
-        } else if line.starts_with("deal with increment") {
-            let n = NUMBER.find(line)
-                .ok_or(err!("number not found in {}", line))?
-                .as_str()
-                .parse()?;
-            Ok(Technique::DealWithIncrement(n))
-        } else if line.starts_with("cut") {
-            let n = NUMBER.find(line)
-                .ok_or(err!("number not found in {}", line))?
-                .as_str()
-                .parse()?;
-            Ok(Technique::Cut(n))
+        } else if let Some(n) = text.strip_prefix("deal with increment ") {
+            match line.parse_at(n.trim(), "an increment")? {
+                n if n > 0 => Ok(Technique::DealWithIncrement(n)),
+                _ => Err(line.error_at(n.trim(), "Expected an increment of at least 1"))
+            }
+        } else if let Some(n) = text.strip_prefix("cut ") {
+            Ok(Technique::Cut(line.parse_at(n.trim(), "a number of cards to cut")?))
         } else {
-            Err(err!("No matching technique for '{}'", line))
+            Err(line.error_at(text, format!("Expected a technique like 'deal into new stack', 'cut N' or 'deal with increment N' but got '{}'", text)))
         }
     }
     fn apply_to_location_no_mod(&self, location: &BigInt, _len: &BigInt) -> BigInt {
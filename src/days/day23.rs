@@ -1,60 +1,94 @@
 use crate::error::Error;
-use self::machine::Machine;
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::Answer;
+use self::machine::{ Machine, Packet };
 use crate::support::intcode::parse_intcode_ops;
+use std::convert::TryFrom;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 23;
+    const TITLE: &'static str = "Category Six";
+    type Args = Pokes;
+    fn solve(input: &str, pokes: Pokes, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input, &pokes))?;
+        stars.part(2, || part2(input, &pokes))?;
+        Ok(())
+    }
+}
+
+/// Send packets around until we see one sent to 255, then
+/// return the Y value of that.
+pub fn part1(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
 
     let ops = parse_intcode_ops(input)?;
 
-    // Send packets around until we see one sent to 255, then
-    // return the Y value of that
-    {
+    let star1 = {
         let mut machines: Vec<Machine> = (0..50)
-            .map(|address| Machine::boot(ops.clone(), address))
+            .map(|address| Machine::boot(ops.clone(), pokes, address))
             .collect::<Result<_,_>>()?;
-        let y = 'outer1: loop {
+        'outer1: loop {
             for idx in 0..machines.len() {
                 if let Some(packet) = machines[idx].step()? {
                     if packet.address == 255 { break 'outer1 packet.y }
-                    machines[packet.address].push_input(packet.x, packet.y);
+                    send(&mut machines, packet)?;
                 }
             }
-        };
-        println!("Star 1: {}", y);
-    }
+            if machines.iter().all(|m| m.is_halted()) {
+                return Err(Error::no_solution("Every machine halted before a packet was sent to 255"))
+            }
+        }
+    };
+    Ok(star1.into())
+}
 
-    // Wait for machines to idle, then send last 255-addressed packet to
-    // address 0. Stop when we send the same Y value twice in this way.
-    {
+/// Wait for machines to idle, then send last 255-addressed packet to
+/// address 0. Stop when we send the same Y value twice in this way.
+pub fn part2(input: &str, pokes: &Pokes) -> Result<Answer,Error> {
+
+    let ops = parse_intcode_ops(input)?;
+
+    let star2 = {
         let mut machines: Vec<Machine> = (0..50)
-            .map(|address| Machine::boot(ops.clone(), address))
+            .map(|address| Machine::boot(ops.clone(), pokes, address))
             .collect::<Result<_,_>>()?;
         let mut packet_for_nat = None;
         let mut last_y = None;
-        let y = 'outer2: loop {
+        'outer2: loop {
             for idx in 0..machines.len() {
                 if let Some(packet) = machines[idx].step()? {
                     if packet.address == 255 {
                         packet_for_nat = Some(packet)
                     } else {
-                        machines[packet.address].push_input(packet.x, packet.y);
+                        send(&mut machines, packet)?;
                     }
                 }
             }
+            if machines.iter().all(|m| m.is_halted()) {
+                return Err(Error::no_solution("Every machine halted before the NAT sent the same Y value twice"))
+            }
             if machines.iter().all(|m| m.is_idle()) {
                 if let Some(packet) = packet_for_nat.take() {
                     if last_y == Some(packet.y) {
-                        break 'outer2 last_y
-                    } else {
-                        last_y = Some(packet.y)
+                        break 'outer2 packet.y
                     }
+                    last_y = Some(packet.y);
                     machines[0].push_input(packet.x, packet.y);
                 }
             }
-        };
-        println!("Star 2: {}", y.unwrap());
-    }
+        }
+    };
+    Ok(star2.into())
+}
 
+/// Pass a packet on to the machine it's addressed to.
+fn send(machines: &mut [Machine], packet: Packet) -> Result<(),Error> {
+    let machine = usize::try_from(packet.address)
+        .ok()
+        .and_then(|address| machines.get_mut(address))
+        .ok_or_else(|| Error::intcode(format!("A packet was sent to address {}, where there's no machine", packet.address)))?;
+    machine.push_input(packet.x, packet.y);
     Ok(())
 }
 
@@ -62,6 +96,7 @@ pub mod machine {
 
     use std::collections::VecDeque;
     use crate::error::Error;
+    use crate::days::Pokes;
     use crate::support::intcode::{
         Intcode,
         Outcome as IntcodeOutcome
@@ -71,22 +106,26 @@ pub mod machine {
         intcode: Intcode,
         input_queue: VecDeque<(i64,i64)>,
         current_input: VecDeque<i64>,
-        current_output: Vec<i64>
+        current_output: Vec<i64>,
+        halted: bool
     }
 
     impl Machine {
-        pub fn boot(ops: Vec<i64>, address: usize) -> Result<Machine,Error> {
+        /// Boot a NIC, making any pokes before it's given its address.
+        pub fn boot(ops: Vec<i64>, pokes: &Pokes, address: usize) -> Result<Machine,Error> {
             let mut intcode = Intcode::new(ops);
+            pokes.apply(&mut intcode)?;
             if let Some(IntcodeOutcome::NeedsInput(p)) = intcode.step()? {
                 intcode.provide_input(p.value(address as i64))?;
             } else {
-                return Err(err!("Could not boot; did not ask for address"));
+                return Err(Error::intcode("Could not boot; did not ask for address"));
             }
             Ok(Machine {
                 intcode,
                 input_queue: VecDeque::new(),
                 current_input: VecDeque::new(),
-                current_output: Vec::new()
+                current_output: Vec::new(),
+                halted: false
             })
         }
         pub fn push_input(&mut self, x: i64, y: i64) {
@@ -97,6 +136,9 @@ pub mod machine {
             && self.current_input.len() == 0
             && self.current_output.len() == 0
         }
+        pub fn is_halted(&self) -> bool {
+            self.halted
+        }
         pub fn step(&mut self) -> Result<Option<Packet>,Error> {
             match self.intcode.step()? {
                 Some(IntcodeOutcome::NeedsInput(p)) => {
@@ -108,7 +150,7 @@ pub mod machine {
                     self.current_output.push(val);
                     if self.current_output.len() == 3 {
                         let packet = Packet{
-                            address: self.current_output[0] as usize,
+                            address: self.current_output[0],
                             x: self.current_output[1],
                             y: self.current_output[2]
                         };
@@ -118,7 +160,10 @@ pub mod machine {
                         Ok(None)
                     }
                 }
-                None => Ok(None)
+                None => {
+                    self.halted = true;
+                    Ok(None)
+                }
             }
         }
         fn get_next_input(&mut self) -> i64 {
@@ -135,7 +180,7 @@ pub mod machine {
 
     #[derive(Debug,Clone,Copy)]
     pub struct Packet {
-        pub address: usize,
+        pub address: i64,
         pub x: i64,
         pub y: i64
     }
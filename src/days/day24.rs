@@ -1,48 +1,82 @@
 use std::collections::HashSet;
+use std::str::FromStr;
 use crate::error::Error;
+use structopt::StructOpt;
+use crate::days::{ Solution, Stars };
+use crate::support::{ animate, Answer };
 use self::bugs::Bugs;
 use self::recursive_bugs::RecursiveBugs;
 
-pub fn both_parts(input: &str) -> Result<(), Error> {
+pub struct Puzzle;
 
-    {
-        let mut bugs = Bugs::from_str(input);
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    #[structopt(long, default_value = "200", help = "How many minutes the recursive bugs spread for in part 2")]
+    minutes: usize
+}
+
+impl Solution for Puzzle {
+    const DAY: usize = 24;
+    const TITLE: &'static str = "Planet of Discord";
+    const ANIMATES: bool = true;
+    type Args = Args;
+    fn solve(input: &str, Args { minutes }: Args, stars: &mut Stars) -> Result<(),Error> {
+        stars.part(1, || part1(input))?;
+        stars.part(2, || part2(input, minutes))?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<Answer,Error> {
+
+    let star1 = {
+        let mut bugs = Bugs::from_str(input)?;
         let mut seen = HashSet::new();
         while !seen.contains(&bugs) {
+            animate::frame(|| format!("Minute {}:\n{}", seen.len(), bugs))?;
             seen.insert(bugs);
             bugs = bugs.step();
         }
-        println!("Star 1: {}", bugs.biodiversity());
-    }
+        bugs.biodiversity()
+    };
+    Ok(star1.into())
+}
 
-    {
+pub fn part2(input: &str, minutes: usize) -> Result<Answer,Error> {
 
-        let mut bugs = RecursiveBugs::from_str(input);
-        for _ in 0..200 {
+    let star2 = {
+        let mut bugs = RecursiveBugs::from_str(input)?;
+        for minute in 0..minutes {
+            animate::frame(|| format!("Minute {}:\n\n{}", minute, bugs))?;
             bugs = bugs.step();
         }
-        let count: u32 = bugs.values().map(|grid| grid.count_true()).sum();
-        println!("Star 2: {}", count);
-    }
-
-    Ok(())
+        bugs.values().map(|grid| grid.count_true()).sum::<u32>()
+    };
+    Ok(star2.into())
 }
 
 /// Recursive bugs for part 2
 pub mod recursive_bugs {
 
     use std::collections::HashMap;
+    use std::fmt;
+    use std::str::FromStr;
+    use crate::error::Error;
     use super::grid::Grid;
 
     #[derive(Debug,Clone)]
     pub struct RecursiveBugs(HashMap<i64,Grid>);
 
-    impl RecursiveBugs {
-        pub fn from_str(input: &str) -> RecursiveBugs {
+    impl FromStr for RecursiveBugs {
+        type Err = Error;
+        fn from_str(input: &str) -> Result<RecursiveBugs,Error> {
             let mut m = HashMap::new();
-            m.insert(0, Grid::from_str(input));
-            RecursiveBugs(m)
+            m.insert(0, Grid::from_str(input)?);
+            Ok(RecursiveBugs(m))
         }
+    }
+
+    impl RecursiveBugs {
         pub fn step(&self) -> RecursiveBugs {
             let (top,bottom) = self.populated_range();
             let mut new_map = HashMap::new();
@@ -67,15 +101,6 @@ pub mod recursive_bugs {
             }
             RecursiveBugs(new_map)
         }
-        #[allow(unused)]
-        pub fn print(&self) {
-            let (top,bottom) = self.populated_range();
-            for level in top..=bottom {
-                println!("Level {}:", level);
-                self.0.get(&level).unwrap_or(&Grid::empty()).print();
-                println!();
-            }
-        }
         fn get_level(&self, level: i64) -> Grid {
             *self.0.get(&level).unwrap_or(&Grid::empty())
         }
@@ -86,6 +111,18 @@ pub mod recursive_bugs {
         }
     }
 
+    /// Each populated level, from the outermost in.
+    impl fmt::Display for RecursiveBugs {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let (top,bottom) = self.populated_range();
+            for level in top..=bottom {
+                writeln!(f, "Level {}:", level)?;
+                writeln!(f, "{}", self.get_level(level))?;
+            }
+            Ok(())
+        }
+    }
+
     impl std::ops::Deref for RecursiveBugs {
         type Target = HashMap<i64,Grid>;
         fn deref(&self) -> &Self::Target {
@@ -140,15 +177,22 @@ pub mod recursive_bugs {
 /// Simple bugs for part 1
 pub mod bugs {
 
+    use std::fmt;
+    use std::str::FromStr;
+    use crate::error::Error;
     use super::grid::Grid;
 
     #[derive(Debug,Clone,Copy,Hash,PartialEq,Eq,PartialOrd,Ord)]
     pub struct Bugs(Grid);
 
-    impl Bugs {
-        pub fn from_str(input: &str) -> Bugs {
-            Bugs(Grid::from_str(input))
+    impl FromStr for Bugs {
+        type Err = Error;
+        fn from_str(input: &str) -> Result<Bugs,Error> {
+            Ok(Bugs(Grid::from_str(input)?))
         }
+    }
+
+    impl Bugs {
         pub fn step(&self) -> Bugs {
             let grid = self.0;
             let mut next = grid;
@@ -172,6 +216,12 @@ pub mod bugs {
 
     }
 
+    impl fmt::Display for Bugs {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
     impl std::ops::Deref for Bugs {
         type Target = Grid;
         fn deref(&self) -> &Grid {
@@ -192,30 +242,45 @@ pub mod bugs {
 /// A 5x5 boolean grid stored in a u32.
 pub mod grid {
 
+    use std::fmt;
+    use std::str::FromStr;
+    use crate::error::Error;
+    use crate::support::{ AsciiMap, Whitespace };
+
     #[derive(Debug,Clone,Copy,Hash,PartialEq,Eq,PartialOrd,Ord)]
     pub struct Grid(u32);
 
+    impl FromStr for Grid {
+        type Err = Error;
+        fn from_str(input: &str) -> Result<Grid,Error> {
+            let map = AsciiMap::parse(input, Whitespace::Strip, |c| match c {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None
+            })?;
+            if map.width() != 5 || map.height() != 5 {
+                return Err(Error::parse_somewhere(format!("Expected a 5x5 map of bugs but it's {}x{}", map.width(), map.height())))
+            }
+            let mut grid = Grid::empty();
+            for (p,&bug) in map.iter() {
+                grid.set(p.x as usize, p.y as usize, bug);
+            }
+            Ok(grid)
+        }
+    }
+
     impl Grid {
         pub fn empty() -> Grid {
             Grid(0)
         }
-        pub fn from_str(input: &str) -> Grid {
-            let mut grid = Grid::empty();
-            for (y,line) in input.trim().lines().enumerate() {
-                for (x,c) in line.trim().chars().enumerate() {
-                    if c == '#' { grid.set(x, y, true) }
-                }
-            }
-            grid
-        }
         pub fn set(&mut self, x: usize, y: usize, value: bool) {
             assert!(x < 5);
             assert!(y < 5);
             let idx = y * 5 + x;
             if value {
-                self.0 = self.0 | 1u32 << idx;
+                self.0 |= 1u32 << idx;
             } else {
-                self.0 = self.0 & !(1u32 << idx);
+                self.0 &= !(1u32 << idx);
             }
         }
         pub fn get(&self, x: usize, y: usize) -> bool {
@@ -230,14 +295,15 @@ pub mod grid {
         pub fn coords() -> impl Iterator<Item=(usize,usize)> {
             (0..5).flat_map(|y| (0..5).map(move |x| (x,y)))
         }
-        #[allow(unused)]
-        pub fn print(&self) {
+    }
+
+    impl fmt::Display for Grid {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             for y in 0..5 {
-                for x in 0..5 {
-                    print!("{}", if self.get(x,y) { '#' } else { '.' });
-                }
-                println!();
+                let row: String = (0..5).map(|x| if self.get(x,y) { '#' } else { '.' }).collect();
+                writeln!(f, "{}", row)?;
             }
+            Ok(())
         }
     }
 
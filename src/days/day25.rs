@@ -1,31 +1,93 @@
+use structopt::StructOpt;
 use crate::error::Error;
-use crate::support::{ Terminal, IntoReadIter };
-use std::io::{ stdin, stdout, Read };
-
-// If interactive mode enabled, explore, pick things up, and find your
-// way to the security door figuring out what items you need to hold to
-// make you the correct weight. Else, run the script which works on
-// my input specifically to give the answer that I needed.
-pub fn part1(input: &str, interactive: bool) -> Result<(), Error> {
-
-    // Read bytes from a script or interactively, depending on the flag
-    let reader: Box<dyn Read> = if interactive {
-        Box::new(stdin())
-    } else {
-        let mut cmds: Vec<u8> = vec![];
-        for line in SCRIPT.trim().lines() {
-            cmds.extend_from_slice(line.trim().as_bytes());
-            cmds.push(b'\n');
+use crate::days::{ Solution, Stars, Pokes };
+use crate::support::{ Terminal, NonAscii, Console, Script, WriteString, Answer, transcript };
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs::File;
+use std::path::{ Path, PathBuf };
+use std::str::FromStr;
+
+pub struct Puzzle;
+
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    #[structopt(long, short, help = "Enable interactive exploration in the terminal")]
+    interactive: bool,
+    #[structopt(long, parse(from_os_str), conflicts_with = "interactive", help = "Run the commands in this script file")]
+    script: Option<PathBuf>,
+    #[structopt(long, parse(from_os_str), help = "Record a transcript of the session to this file")]
+    record: Option<PathBuf>,
+    #[structopt(long, parse(from_os_str), conflicts_with = "interactive", help = "Replay a recorded transcript, checking the output matches")]
+    replay: Option<PathBuf>,
+    #[structopt(flatten)]
+    pokes: Pokes
+}
+
+impl Solution for Puzzle {
+    const DAY: usize = 25;
+    const TITLE: &'static str = "Cryostasis";
+    type Args = Args;
+    fn solve(input: &str, args: Args, stars: &mut Stars) -> Result<(),Error> {
+        if let Some(transcript) = args.replay {
+            stars.result(replay(input, &transcript)?);
+        } else if args.interactive {
+            interactive(input, &args.pokes, args.record.as_deref())?;
+        } else {
+            stars.part(1, || part1(input, &args.pokes, args.script.as_deref(), args.record.as_deref()))?;
         }
-        Box::new(cmds.into_iter().into_reader())
+        // There is no part 2.
+        Ok(())
+    }
+}
+
+// Run a script; by default the one which works on my input specifically
+// to give the answer that I needed, and pick the password out of the
+// output. The session can be recorded to a transcript to be replayed later.
+pub fn part1(input: &str, pokes: &Pokes, script: Option<&Path>, record: Option<&Path>) -> Result<Answer, Error> {
+    let script = match script {
+        Some(path) => Script::from_file(path)?,
+        None => Script::from_str(SCRIPT)?
     };
+    let mut output = WriteString::new();
+    let (reader, writer) = script.into_io(&mut output);
+    {
+        let mut terminal = Terminal::new(pokes.machine(input)?, reader.clone(), writer)
+            .non_ascii(NonAscii::Inline);
+        if let Some(path) = record {
+            terminal.record(File::create(path)?);
+        }
+        terminal.run()?;
+    }
+    reader.finish()?;
+
+    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"typing (\d+) on the keypad").unwrap());
+    let output = output.into_string();
+    match RE.captures(&output) {
+        Some(caps) => Ok(caps[1].parse::<u64>()?.into()),
+        None => Err(Error::no_solution(format!("No password was given:\n{}", output)))
+    }
+}
 
-    // Pass in our reade, and output to stdout until the program ends.
-    let mut terminal = Terminal::from_str(input, reader, stdout())?;
-    while let Some(_) = terminal.step()? {}
+// Explore, pick things up, and find your way to the security door
+// figuring out what items you need to hold to make you the correct weight.
+pub fn interactive(input: &str, pokes: &Pokes, record: Option<&Path>) -> Result<(), Error> {
+    let mut console = Console::new(pokes.machine(input)?).non_ascii(NonAscii::Inline);
+    if let Some(path) = record {
+        console.record(File::create(path)?);
+    }
+    console.run()?;
     Ok(())
 }
 
+// Replay the inputs from a recorded session, checking that we see exactly
+// the output that was recorded.
+pub fn replay(input: &str, transcript: &Path) -> Result<Answer, Error> {
+    let recorded = std::fs::read_to_string(transcript)?;
+    transcript::replay(input, &recorded).map_err(|e| e.in_file(transcript.display().to_string()))?;
+    Ok("Transcript replayed successfully".into())
+}
+
 
 static SCRIPT: &str = "
     north
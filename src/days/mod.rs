@@ -1,3 +1,13 @@
+use std::io::{ self, Read };
+use std::marker::PhantomData;
+use std::path::Path;
+use std::time::{ Duration, Instant };
+use structopt::StructOpt;
+use structopt::clap::{ App, Arg, ArgMatches };
+use crate::error::Error;
+use crate::support::{ animate, render, Answer, Intcode };
+use crate::support::intcode::Poke;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -23,3 +33,347 @@ pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+
+/// Every day, in order. Each module describes its own day with
+/// a `Puzzle` that implements `Solution`, and just needs adding here.
+pub fn all() -> Vec<Box<dyn Day>> {
+    vec![
+        register::<day01::Puzzle>(),
+        register::<day02::Puzzle>(),
+        register::<day03::Puzzle>(),
+        register::<day04::Puzzle>(),
+        register::<day05::Puzzle>(),
+        register::<day06::Puzzle>(),
+        register::<day07::Puzzle>(),
+        register::<day08::Puzzle>(),
+        register::<day09::Puzzle>(),
+        register::<day10::Puzzle>(),
+        register::<day11::Puzzle>(),
+        register::<day12::Puzzle>(),
+        register::<day13::Puzzle>(),
+        register::<day14::Puzzle>(),
+        register::<day15::Puzzle>(),
+        register::<day16::Puzzle>(),
+        register::<day17::Puzzle>(),
+        register::<day18::Puzzle>(),
+        register::<day19::Puzzle>(),
+        register::<day20::Puzzle>(),
+        register::<day21::Puzzle>(),
+        register::<day22::Puzzle>(),
+        register::<day23::Puzzle>(),
+        register::<day24::Puzzle>(),
+        register::<day25::Puzzle>(),
+    ]
+}
+
+/// What each day needs to say about itself: which day it is, what
+/// arguments it takes, and how to work out the answers.
+pub trait Solution {
+    /// Which day of the advent calendar this is.
+    const DAY: usize;
+    /// The title of the puzzle, which is shown in the help.
+    const TITLE: &'static str;
+    /// Does this day read a puzzle input from a file? Day 4 doesn't.
+    const TAKES_INPUT: bool = true;
+    /// Does this day draw pictures that can be written to a file with
+    /// `--render`? See `support::render`.
+    const DRAWS_PICTURES: bool = false;
+    /// Does this day run a simulation that can be watched with
+    /// `--animate`? See `support::animate`.
+    const ANIMATES: bool = false;
+    /// Any arguments the day takes on top of its input file. Every one of
+    /// these should have a default, so that the day can be run without any.
+    type Args: StructOpt;
+    /// Solve the puzzle, handing each answer to `stars` as it's found.
+    fn solve(input: &str, args: Self::Args, stars: &mut Stars) -> Result<(),Error>;
+}
+
+/// For days that take no arguments beyond their input file.
+#[derive(Debug, StructOpt)]
+pub struct NoArgs {}
+
+/// For days that run Intcode programs, which can have their memory
+/// changed before they start with `--poke`. Days flatten this into their
+/// `Args`, and decide which machines to make the pokes to.
+#[derive(Debug, Default, StructOpt)]
+pub struct Pokes {
+    #[structopt(long = "poke", value_name = "ADDR=VALUE", number_of_values = 1,
+        help = "Set an address in the Intcode program's memory before it runs (can be given more than once)")]
+    pokes: Vec<Poke>
+}
+
+impl Pokes {
+    /// Make the pokes to a machine that hasn't started yet, after any
+    /// that the day made itself (so these win).
+    pub fn apply(&self, intcode: &mut Intcode) -> Result<(),Error> {
+        self.pokes.iter().try_for_each(|&poke| intcode.poke(poke))
+    }
+    /// A machine running the program in `input`, with the pokes made to it.
+    pub fn machine(&self, input: &str) -> Result<Intcode,Error> {
+        let mut intcode: Intcode = input.parse()?;
+        self.apply(&mut intcode)?;
+        Ok(intcode)
+    }
+}
+
+/// A `Solution`, with the details of its arguments hidden away so that
+/// every day can be handled the same way.
+pub trait Day: Send + Sync {
+    /// Which day of the advent calendar this is.
+    fn number(&self) -> usize;
+    /// The name of the subcommand that runs this day.
+    fn name(&self) -> String {
+        format!("day{}", self.number())
+    }
+    /// The subcommand that runs this day.
+    fn command(&self) -> App<'static,'static>;
+    /// Where the day's input is found if no file is given.
+    fn default_input(&self, input_dir: &Path) -> String {
+        input_dir.join(format!("day{:02}.txt", self.number())).display().to_string()
+    }
+    /// Run the day given the arguments matched for its subcommand. If no
+    /// input file was given, it's read from `input_dir`.
+    fn run(&self, matches: &ArgMatches, input_dir: &Path, stars: &mut Stars) -> Result<(),Error>;
+    /// Run the day with its input from `input_dir` and default arguments.
+    fn run_default(&self, input_dir: &Path, stars: &mut Stars) -> Result<(),Error>;
+}
+
+fn register<S: Solution + 'static>() -> Box<dyn Day> {
+    Box::new(Registered::<S>(PhantomData))
+}
+
+// No `S` is ever held, so this is `Send` and `Sync` whatever `S` is:
+struct Registered<S>(PhantomData<fn() -> S>);
+
+impl <S: Solution> Registered<S> {
+    fn solve(&self, path: &str, args: S::Args, stars: &mut Stars) -> Result<(),Error> {
+        if !S::TAKES_INPUT {
+            stars.restart();
+            return S::solve("", args, stars)
+        }
+        let input = read_input(path)?;
+        let name = if path == "-" { "<stdin>" } else { path };
+        stars.restart();
+        S::solve(&input, args, stars).map_err(|e| e.in_file(name))
+    }
+}
+
+impl <S: Solution> Day for Registered<S> {
+    fn number(&self) -> usize {
+        S::DAY
+    }
+    fn command(&self) -> App<'static,'static> {
+        let app = S::Args::clap()
+            .name(self.name())
+            .about(S::TITLE)
+            .arg(Arg::with_name("part")
+                .long("part")
+                .value_name("PART")
+                .possible_values(&["1", "2"])
+                .help("Only work out the answer to this part of the puzzle"));
+        let app = if S::DRAWS_PICTURES {
+            app.arg(Arg::with_name("render")
+                    .long("render")
+                    .value_name("FILE")
+                    .help("Write the pictures drawn along the way to a .pbm, .pgm or .png file"))
+                .arg(Arg::with_name("scale")
+                    .long("scale")
+                    .value_name("N")
+                    .default_value("1")
+                    .help("Draw each pixel of a rendered picture N pixels wide and high"))
+        } else {
+            app
+        };
+        let app = if S::ANIMATES {
+            app.arg(Arg::with_name("animate")
+                    .long("animate")
+                    .conflicts_with("frames")
+                    .help("Watch the simulation play out on stderr"))
+                .arg(Arg::with_name("frames")
+                    .long("frames")
+                    .value_name("DIR")
+                    .help("Write each frame of the simulation to a file in DIR"))
+                .arg(Arg::with_name("fps")
+                    .long("fps")
+                    .value_name("N")
+                    .default_value("20")
+                    .help("How many frames to show a second with --animate (0 for as many as possible)"))
+                .arg(Arg::with_name("every")
+                    .long("every")
+                    .value_name("N")
+                    .default_value("1")
+                    .help("Only show or write every Nth frame"))
+        } else {
+            app
+        };
+        if S::TAKES_INPUT {
+            app.arg(Arg::with_name("FILE").help("The puzzle input, or '-' to read it from stdin [default: dayNN.txt in the input folder]"))
+        } else {
+            app
+        }
+    }
+    fn run(&self, matches: &ArgMatches, input_dir: &Path, stars: &mut Stars) -> Result<(),Error> {
+        let args = S::Args::from_clap(matches);
+        if let Some(part) = matches.value_of("part") {
+            stars.only(part.parse()?);
+        }
+        if let Some(path) = matches.value_of("render") {
+            let scale = matches.value_of("scale").unwrap_or("1").parse()
+                .map_err(|_| err!("The scale should be a whole number of pixels"))?;
+            render::render_to(path, scale)?;
+        }
+        let every = || matches.value_of("every").unwrap_or("1").parse()
+            .map_err(|_| err!("Frames to skip should be given as a whole number"));
+        if matches.is_present("animate") {
+            let fps = matches.value_of("fps").unwrap_or("20").parse()
+                .map_err(|_| err!("The frame rate should be a number of frames a second"))?;
+            animate::animate_in_terminal(fps, every()?)?;
+        } else if let Some(dir) = matches.value_of("frames") {
+            animate::dump_frames_to(dir, every()?)?;
+        }
+        let path = match matches.value_of("FILE") {
+            Some(path) => path.to_owned(),
+            None => self.default_input(input_dir)
+        };
+        self.solve(&path, args, stars)
+    }
+    fn run_default(&self, input_dir: &Path, stars: &mut Stars) -> Result<(),Error> {
+        let args = S::Args::from_iter_safe(&[self.name()])?;
+        self.solve(&self.default_input(input_dir), args, stars)
+    }
+}
+
+/// Read a puzzle input from a file, or from stdin if the path is '-'.
+fn read_input(path: &str) -> Result<String,Error> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)
+            .map_err(|e| io::Error::new(e.kind(), format!("Cannot read input from stdin: {}", e)))?;
+        Ok(input)
+    } else {
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("Cannot read input '{}': {}", path, e)))?;
+        Ok(input)
+    }
+}
+
+/// Collects the answers that a solution finds, noting how long each took,
+/// and hands each one on to a callback as soon as it's found.
+pub struct Stars<'a> {
+    last: Instant,
+    only: Option<usize>,
+    stars: Vec<Star>,
+    on_star: Box<dyn FnMut(&Star) + 'a>
+}
+
+/// An answer that a solution found.
+#[derive(Debug,Clone)]
+pub struct Star {
+    /// Which part of the puzzle this answers, or None if it's the result
+    /// of some other mode that a day can run in.
+    pub part: Option<usize>,
+    pub answer: Answer,
+    /// How long it took to work out this answer.
+    pub elapsed: Duration
+}
+
+impl <'a> Stars<'a> {
+
+    pub fn new<F: FnMut(&Star) + 'a>(on_star: F) -> Stars<'a> {
+        Stars { last: Instant::now(), only: None, stars: vec![], on_star: Box::new(on_star) }
+    }
+
+    /// Only work out the answer to one part of the puzzle.
+    pub fn only(&mut self, part: usize) {
+        self.only = Some(part);
+    }
+
+    /// The answers found so far.
+    pub fn stars(&self) -> &[Star] {
+        &self.stars
+    }
+
+    /// Work out the answer to one part of the puzzle, unless we've
+    /// been asked for a different part only.
+    pub fn part<F: FnOnce() -> Result<Answer,Error>>(&mut self, part: usize, f: F) -> Result<(),Error> {
+        if self.only.map(|only| only != part).unwrap_or(false) {
+            return Ok(())
+        }
+        let answer = f()?;
+        let elapsed = self.lap();
+        self.push(Star { part: Some(part), answer, elapsed });
+        Ok(())
+    }
+
+    /// Some result that isn't the answer to a part of the puzzle.
+    pub fn result(&mut self, answer: Answer) {
+        let elapsed = self.lap();
+        self.push(Star { part: None, answer, elapsed });
+    }
+
+    fn restart(&mut self) {
+        self.last = Instant::now();
+    }
+
+    fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now - self.last;
+        self.last = now;
+        elapsed
+    }
+
+    fn push(&mut self, star: Star) {
+        (self.on_star)(&star);
+        self.stars.push(star);
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn pokes_are_made_after_the_days_own() {
+        let pokes = Pokes::from_iter_safe(&["day2", "--poke", "0=2", "--poke", "4=-1"]).unwrap();
+        let intcode = pokes.machine("1,0,0,0,99").unwrap();
+        assert_eq!(intcode.ops().as_slice(), &[2,0,0,0,-1]);
+        let mut intcode = Intcode::with_pokes(vec![1,0,0,0,99], &[Poke::new(0, 1)]);
+        pokes.apply(&mut intcode).unwrap();
+        assert_eq!(intcode.get_op(0), 2);
+    }
+
+    #[test]
+    fn every_day_is_registered_in_order() {
+        let days = all();
+        let numbers: Vec<usize> = days.iter().map(|day| day.number()).collect();
+        assert_eq!(numbers, (1..=25).collect::<Vec<_>>());
+        for day in &days {
+            assert_eq!(day.command().get_name(), day.name());
+        }
+    }
+
+    #[test]
+    fn stars_hand_on_answers_as_they_are_found() {
+        let mut seen = vec![];
+        let mut stars = Stars::new(|star: &Star| seen.push(star.part));
+        stars.part(1, || Ok(10usize.into())).unwrap();
+        stars.part(2, || Ok(20usize.into())).unwrap();
+        stars.result("done".into());
+        assert_eq!(stars.stars().len(), 3);
+        drop(stars);
+        assert_eq!(seen, vec![Some(1), Some(2), None]);
+    }
+
+    #[test]
+    fn stars_only_work_out_the_part_asked_for() {
+        let mut stars = Stars::new(|_| {});
+        stars.only(2);
+        stars.part(1, || panic!("part 1 shouldn't be worked out")).unwrap();
+        stars.part(2, || Ok(20usize.into())).unwrap();
+        let parts: Vec<Option<usize>> = stars.stars().iter().map(|s| s.part).collect();
+        assert_eq!(parts, vec![Some(2)]);
+    }
+
+}
@@ -1,4 +1,149 @@
-pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+
+/// Everything that can go wrong. Most errors are just a message (built
+/// with `err!`), but those that callers may want to tell apart have a
+/// variant of their own; see `Error::exit_code`.
+#[derive(Debug)]
+pub enum Error {
+    /// Something went wrong reading or writing.
+    Io(io::Error),
+    /// Some input couldn't be parsed. `position` is the line and column
+    /// it went wrong at (counting from 1), if that's known.
+    Parse { file: Option<String>, position: Option<(usize,usize)>, message: String },
+    /// An Intcode program did something it shouldn't have.
+    Intcode(String),
+    /// The input was fine, but no answer could be found for it.
+    NoSolution(String),
+    /// Anything else.
+    Message(String)
+}
+
+impl Error {
+
+    /// An error parsing the input at some line and column.
+    pub fn parse<S: Into<String>>(line: usize, column: usize, message: S) -> Error {
+        Error::Parse { file: None, position: Some((line, column)), message: message.into() }
+    }
+
+    /// An error parsing some input, without knowing where in it.
+    pub fn parse_somewhere<S: Into<String>>(message: S) -> Error {
+        Error::Parse { file: None, position: None, message: message.into() }
+    }
+
+    /// An Intcode program misbehaved.
+    pub fn intcode<S: Into<String>>(message: S) -> Error {
+        Error::Intcode(message.into())
+    }
+
+    /// The input has no answer.
+    pub fn no_solution<S: Into<String>>(message: S) -> Error {
+        Error::NoSolution(message.into())
+    }
+
+    /// Note the file that some input came from, if this is a parse
+    /// error that doesn't know already.
+    pub fn in_file<S: Into<String>>(self, name: S) -> Error {
+        match self {
+            Error::Parse { file: None, position, message } => {
+                Error::Parse { file: Some(name.into()), position, message }
+            },
+            e => e
+        }
+    }
+
+    /// If this is an I/O error, what kind is it?
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            Error::Io(e) => Some(e.kind()),
+            _ => None
+        }
+    }
+
+    /// The status that the binary exits with on this error, so that
+    /// scripts running it can tell what went wrong.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Message(_) => 1,
+            Error::Io(_) => 2,
+            Error::Parse { .. } => 3,
+            Error::Intcode(_) => 4,
+            Error::NoSolution(_) => 5
+        }
+    }
+
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Parse { file: Some(file), position: Some((line, column)), message } => write!(f, "{}:{}:{}: {}", file, line, column, message),
+            Error::Parse { file: Some(file), position: None, message } => write!(f, "{}: {}", file, message),
+            Error::Parse { file: None, position: Some((line, column)), message } => write!(f, "line {}, column {}: {}", line, column, message),
+            Error::Parse { file: None, position: None, message } => write!(f, "{}", message),
+            Error::Intcode(s) => write!(f, "Intcode error: {}", s),
+            Error::NoSolution(s) => write!(f, "No solution: {}", s),
+            Error::Message(s) => write!(f, "{}", s)
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+/// Our own errors sometimes have to be passed through things that only
+/// know about `io::Error` (a `Read`, say), so those are unwrapped again.
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        if e.get_ref().map(|inner| inner.is::<Error>()).unwrap_or(false) {
+            *e.into_inner().and_then(|inner| inner.downcast().ok()).unwrap()
+        } else {
+            Error::Io(e)
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(s: String) -> Error {
+        Error::Message(s)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(s: &str) -> Error {
+        Error::Message(s.to_owned())
+    }
+}
+
+/// Numbers that can't be parsed are bad input, so these are parse errors
+/// (but without a position, so prefer `Line::parse_at` where there is one).
+impl From<ParseIntError> for Error {
+    fn from(e: ParseIntError) -> Error {
+        Error::parse_somewhere(format!("Cannot parse a number: {}", e))
+    }
+}
+
+impl From<structopt::clap::Error> for Error {
+    fn from(e: structopt::clap::Error) -> Error {
+        Error::Message(e.message)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Error {
+        // toml counts lines and columns from 0:
+        let (line, column) = e.line_col().unwrap_or((0,0));
+        Error::parse(line + 1, column + 1, e.to_string())
+    }
+}
 
 #[macro_export]
 macro_rules! err {
@@ -6,4 +151,39 @@ macro_rules! err {
         let err: Error = format!($($tt)*).into();
         err
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn errors_have_distinct_exit_codes() {
+        let errors = [
+            err!("oops"),
+            io::Error::from(io::ErrorKind::NotFound).into(),
+            Error::parse(1, 2, "bad"),
+            Error::intcode("bad"),
+            Error::no_solution("none")
+        ];
+        let mut codes: Vec<i32> = errors.iter().map(|e| e.exit_code()).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|&c| c != 0));
+    }
+
+    #[test]
+    fn parse_errors_say_where() {
+        let e = Error::parse(3, 7, "Invalid direction");
+        assert_eq!(e.to_string(), "line 3, column 7: Invalid direction");
+        let e = e.in_file("inputs/day03.txt");
+        assert_eq!(e.to_string(), "inputs/day03.txt:3:7: Invalid direction");
+
+        let e: Error = "x".parse::<u8>().unwrap_err().into();
+        assert_eq!(e.exit_code(), 3);
+        assert_eq!(e.in_file("in.txt").to_string(), "in.txt: Cannot parse a number: invalid digit found in string");
+    }
+
+}
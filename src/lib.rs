@@ -0,0 +1,20 @@
+//! Solutions to Advent of Code 2019, along with the pieces they're built
+//! from. `support` has the Intcode interpreter and friends (`Terminal`
+//! for ASCII programs, scripts, transcripts and so on), and `days` has a
+//! module per day, each of which can be run through the `Solution` trait.
+//!
+//! ```
+//! use advent_of_code_2019::support::{ Intcode, Outcome };
+//!
+//! // Output 42 and then finish:
+//! let mut intcode: Intcode = "104,42,99".parse().unwrap();
+//! match intcode.step().unwrap() {
+//!     Some(Outcome::Output(n)) => assert_eq!(n, 42),
+//!     other => panic!("expected an output but got {:?}", other)
+//! }
+//! assert!(intcode.step().unwrap().is_none());
+//! ```
+
+#[macro_use] pub mod error;
+pub mod days;
+pub mod support;
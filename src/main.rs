@@ -1,197 +1,402 @@
-#[macro_use] mod error;
-mod days;
-mod support;
-
-use error::Error;
-use std::path::PathBuf;
-use structopt::StructOpt;
-
-/// A table-of-contents of the subcommands and their
-/// arguments for this program.
-#[derive(Debug, StructOpt)]
-#[structopt(name = "aoc2019", about = "AoC2019 solutions")]
-enum Day {
-    Day1(FileInput),
-    Day2(FileInput),
-    Day3(FileInput),
-    Day4 {
-        #[structopt(help = "The first number in the range")]
-        low: usize,
-        #[structopt(help = "The last number in the range")]
-        high: usize
-    },
-    Day5(FileInput),
-    Day6(FileInput),
-    Day7(FileInput),
-    Day8 {
-        #[structopt(name = "FILE", parse(from_os_str))]
-        input: PathBuf,
-        #[structopt(long, default_value = "25", help = "The image width")]
-        image_width: usize,
-        #[structopt(long, default_value = "6",  help = "The image height")]
-        image_height: usize
-    },
-    Day9(FileInput),
-    Day10(FileInput),
-    Day11(FileInput),
-    Day12(FileInput),
-    Day13(FileInput),
-    Day14(FileInput),
-    Day15(FileInput),
-    Day16(FileInput),
-    Day17(FileInput),
-    Day18(FileInput),
-    Day19(FileInput),
-    Day20(FileInput),
-    Day21 {
-        #[structopt(name = "FILE", parse(from_os_str))]
-        input: PathBuf,
-        #[structopt(long, short, help = "Enable interactive springbot programming")]
-        interactive: bool
-    },
-    Day22(FileInput),
-    Day23(FileInput),
-    Day24(FileInput),
-    Day25 {
-        #[structopt(name = "FILE", parse(from_os_str))]
-        input: PathBuf,
-        #[structopt(long, short, help = "Enable interactive exploration in the terminal")]
-        interactive: bool
-    },
-}
+use advent_of_code_2019::{ days, err };
+use advent_of_code_2019::error::Error;
+use advent_of_code_2019::days::{ Day, Star, Stars };
+use advent_of_code_2019::support::{ progress, render, Answer, Expected, Description, Examples };
+use std::any::Any;
+use std::convert::TryFrom;
+use std::panic::{ self, AssertUnwindSafe };
+use std::path::Path;
+use std::time::Duration;
+use serde_json::{ json, Value };
+use rayon::prelude::*;
+use structopt::clap::{ App, AppSettings, Arg, ArgMatches, SubCommand };
 
-/// Days that take a file as input take one input arg:
-#[derive(Debug, StructOpt)]
-struct FileInput {
-    #[structopt(name = "FILE", parse(from_os_str))]
-    input: PathBuf,
+/// A table-of-contents of the subcommands and their arguments
+/// for this program. Each day provides its own subcommand.
+fn app(days: &[Box<dyn Day>]) -> App<'static,'static> {
+    App::new("aoc2019")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("AoC2019 solutions")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::DeriveDisplayOrder)
+        .arg(Arg::with_name("format")
+            .long("format")
+            .value_name("FORMAT")
+            .possible_values(&["text", "json"])
+            .default_value("text")
+            .global(true)
+            .help("Print answers as text, or as a JSON record per line"))
+        .arg(Arg::with_name("input-dir")
+            .long("input-dir")
+            .value_name("DIR")
+            .global(true)
+            .help("The folder that inputs are read from when no file is given [default: inputs]"))
+        .arg(Arg::with_name("progress")
+            .long("progress")
+            .global(true)
+            .help("Show how long-running parts are getting on, on stderr"))
+        .subcommands(days.iter().map(|day| day.command()))
+        .subcommand(SubCommand::with_name("all")
+            .about("Run every day against its input in the input folder, timing each part"))
+        .subcommand(SubCommand::with_name("verify")
+            .about("Check that days give the answers that we expect for their inputs")
+            .arg(Arg::with_name("answers")
+                .long("answers")
+                .value_name("FILE")
+                .default_value("answers.toml")
+                .help("The file of expected answers"))
+            .arg(Arg::with_name("DAY")
+                .multiple(true)
+                .help("The days to check (all of them if none are given)")))
+        .subcommand(SubCommand::with_name("describe")
+            .about("Print the description of a day's puzzle")
+            .arg(Arg::with_name("DAY")
+                .required(true)
+                .help("The day to describe"))
+            .arg(Arg::with_name("width")
+                .long("width")
+                .value_name("COLUMNS")
+                .default_value("80")
+                .help("The width to wrap the description to")))
+        .subcommand(SubCommand::with_name("examples")
+            .about("Pick out the worked examples in days' descriptions, to be checked by hand and kept in the examples folder")
+            .arg(Arg::with_name("out")
+                .long("out")
+                .value_name("DIR")
+                .help("Write the examples for each day to dayNN.toml in this folder, rather than printing them"))
+            .arg(Arg::with_name("DAY")
+                .multiple(true)
+                .help("The days to find examples for (all of them if none are given)")))
 }
 
-/// Act on the subcommands and such provided using
-/// the `Day` enum.
-fn day(day: Day) -> Result<(),Error> {
-    use self::Day::*;
-    match day {
-        Day1(FileInput { input }) => {
-            let s = read(input)?;
-            days::day01::part1(&s)?;
-            days::day01::part2(&s)?;
-        },
-        Day2(FileInput { input }) => {
-            let s = read(input)?;
-            days::day02::part1(&s)?;
-            days::day02::part2(&s)?;
-        },
-        Day3(FileInput { input }) => {
-            let s = read(input)?;
-            days::day03::part1(&s)?;
-            days::day03::part2(&s)?;
-        },
-        Day4 { low, high } => {
-            days::day04::part1(low, high)?;
-            days::day04::part2(low, high)?;
-        },
-        Day5(FileInput { input }) => {
-            let s = read(input)?;
-            days::day05::part1(&s)?;
-            days::day05::part2(&s)?;
-        },
-        Day6(FileInput { input }) => {
-            let s = read(input)?;
-            days::day06::both_parts(&s)?;
-        },
-        Day7(FileInput { input }) => {
-            let s = read(input)?;
-            days::day07::both_parts(&s)?;
-        },
-        Day8 { input, image_width, image_height } => {
-            let s = read(input)?;
-            days::day08::part1(&s, image_width, image_height)?;
-            days::day08::part2(&s, image_width, image_height)?;
-        },
-        Day9(FileInput { input }) => {
-            let s = read(input)?;
-            days::day09::part1(&s)?;
-            days::day09::part2(&s)?;
-        },
-        Day10(FileInput { input }) => {
-            let s = read(input)?;
-            days::day10::both_parts(&s)?;
-        },
-        Day11(FileInput { input }) => {
-            let s = read(input)?;
-            days::day11::both_parts(&s)?;
-        },
-        Day12(FileInput { input }) => {
-            let s = read(input)?;
-            days::day12::both_parts(&s)?;
-        },
-        Day13(FileInput { input }) => {
-            let s = read(input)?;
-            days::day13::both_parts(&s)?;
-        },
-        Day14(FileInput { input }) => {
-            let s = read(input)?;
-            days::day14::both_parts(&s)?;
-        },
-        Day15(FileInput { input }) => {
-            let s = read(input)?;
-            days::day15::both_parts(&s)?;
-        },
-        Day16(FileInput { input }) => {
-            let s = read(input)?;
-            days::day16::both_parts(&s)?;
-        },
-        Day17(FileInput { input }) => {
-            let s = read(input)?;
-            days::day17::both_parts(&s)?;
-        },
-        Day18(FileInput { input }) => {
-            let s = read(input)?;
-            days::day18::both_parts(&s)?;
-        },
-        Day19(FileInput { input }) => {
-            let s = read(input)?;
-            days::day19::both_parts(&s)?;
-        },
-        Day20(FileInput { input }) => {
-            let s = read(input)?;
-            days::day20::both_parts(&s)?;
-        },
-        Day21 { input, interactive } => {
-            let s = read(input)?;
-            days::day21::both_parts(&s, interactive)?;
+/// Act on the subcommand and arguments provided.
+fn run(days: &[Box<dyn Day>], matches: &ArgMatches) -> Result<(),Error> {
+    let format = Format::from_matches(matches);
+    let input_dir = input_dir(matches);
+    // Days run side by side in 'all', so their progress lines would overlap:
+    let wants_progress = matches.is_present("progress") || matches.subcommand().1.map(|m| m.is_present("progress")).unwrap_or(false);
+    if wants_progress && matches.subcommand_name() != Some("all") {
+        progress::enable();
+    }
+    match matches.subcommand() {
+        ("all", _) => {
+            all(days, input_dir, format)
         },
-        Day22(FileInput { input }) => {
-            let s = read(input)?;
-            days::day22::both_parts(&s)?;
+        ("verify", Some(matches)) => {
+            verify(days, input_dir, matches)
         },
-        Day23(FileInput { input }) => {
-            let s = read(input)?;
-            days::day23::both_parts(&s)?;
+        ("describe", Some(matches)) => {
+            describe(matches)
         },
-        Day24(FileInput { input }) => {
-            let s = read(input)?;
-            days::day24::both_parts(&s)?;
+        ("examples", Some(matches)) => {
+            examples(days, matches)
         },
-        Day25 { input, interactive } => {
-            let s = read(input)?;
-            days::day25::part1(&s, interactive)?;
-            // There is no part 2.
+        (name, Some(matches)) => {
+            let day = days
+                .iter()
+                .find(|day| day.name() == name)
+                .ok_or_else(|| err!("There is no subcommand '{}'", name))?;
+            let n = day.number();
+            day.run(matches, input_dir, &mut Stars::new(move |star: &Star| format.print(n, star)))
         },
-    };
-    Ok(())
+        (name, None) => {
+            Err(err!("There is no subcommand '{}'", name))
+        }
+    }
+}
+
+/// The folder to read inputs from, which like the format
+/// can be given before or after the subcommand.
+fn input_dir<'a>(matches: &'a ArgMatches) -> &'a Path {
+    let sub_dir = matches.subcommand().1.and_then(|m| m.value_of("input-dir"));
+    Path::new(sub_dir.or_else(|| matches.value_of("input-dir")).unwrap_or("inputs"))
 }
 
 /// Parse the arguments, run the relevant code and
-/// print any errors to stderr.
+/// print any errors to stderr, exiting with a code
+/// that says what kind of error it was.
 fn main() {
-    if let Err(e) = day(Day::from_args()) {
+    let days = days::all();
+    let matches = app(&days).get_matches();
+    if let Err(e) = run(&days, &matches) {
         eprintln!("{}", e);
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// How answers are printed.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+enum Format {
+    Text,
+    Json
+}
+
+impl Format {
+
+    /// The format can be given before or after the subcommand.
+    fn from_matches(matches: &ArgMatches) -> Format {
+        let given = |m: &ArgMatches| m.occurrences_of("format") > 0 && m.value_of("format") == Some("json");
+        let sub_given = matches.subcommand().1.map(given).unwrap_or(false);
+        if given(matches) || sub_given { Format::Json } else { Format::Text }
+    }
+
+    /// Print an answer as soon as it's found.
+    fn print(self, day: usize, star: &Star) {
+        print!("{}", self.render(day, star));
+    }
+
+    /// An answer as it's printed, ending in a newline.
+    fn render(self, day: usize, star: &Star) -> String {
+        match self {
+            Format::Text => {
+                let label = match star.part {
+                    Some(n) => format!("Star {}", n),
+                    None => "Result".to_owned()
+                };
+                match &star.answer {
+                    Answer::Image(image) => format!("{}:\n{}", label, image),
+                    answer => format!("{}: {}\n", label, answer)
+                }
+            },
+            Format::Json => {
+                let record = json!({
+                    "day": day,
+                    "part": star.part,
+                    "answer": answer_json(&star.answer),
+                    "elapsed_ms": star.elapsed.as_secs_f64() * 1000.0
+                });
+                format!("{}\n", record)
+            }
+        }
+    }
+
+}
+
+/// Numbers are numbers where they fit, and images are arrays
+/// of rows, with '#' for each lit pixel.
+fn answer_json(answer: &Answer) -> Value {
+    match answer {
+        Answer::Number(n) if i64::try_from(*n).is_ok() => json!(*n as i64),
+        Answer::Number(n) => json!(n.to_string()),
+        Answer::Text(s) => json!(s),
+        Answer::Image(image) => {
+            let rows: Vec<String> = image
+                .rows()
+                .map(|row| row.iter().map(|&lit| if lit { '#' } else { ' ' }).collect())
+                .collect();
+            json!(rows)
+        }
+    }
+}
+
+/// Run every day with its default input, and then print a table
+/// showing how long each part took (unless printing JSON, where
+/// each record has the time taken already). Days are run in parallel,
+/// so their answers are collected up and printed in order once they're
+/// all done, and a day that panics is reported as failing rather than
+/// taking the rest down with it.
+fn all(days: &[Box<dyn Day>], input_dir: &Path, format: Format) -> Result<(),Error> {
+    // Maps that days draw would get jumbled up, so they aren't shown:
+    render::hide_drawings();
+    // Panics are reported along with the day they happened in, below,
+    // rather than as they happen:
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let results: Vec<_> = days
+        .par_iter()
+        .map(|day| {
+            let n = day.number();
+            let mut output = String::new();
+            let (res, stars) = {
+                let mut stars = Stars::new(|star: &Star| output.push_str(&format.render(n, star)));
+                let res = panic::catch_unwind(AssertUnwindSafe(|| day.run_default(input_dir, &mut stars)))
+                    .unwrap_or_else(|payload| Err(err!("Panicked: {}", panic_message(&*payload))));
+                (res, stars.stars().to_vec())
+            };
+            (n, res, stars, output)
+        })
+        .collect();
+    panic::set_hook(hook);
+
+    for (n, res, _, output) in &results {
+        if format == Format::Text {
+            println!("Day {}:", n);
+        }
+        print!("{}", output);
+        if let Err(e) = res {
+            eprintln!("Day {} failed: {}", n, e);
+        }
+        if format == Format::Text {
+            println!();
+        }
+    }
+    let results: Vec<_> = results.into_iter().map(|(n, res, stars, _)| (n, res.is_ok(), stars)).collect();
+    let failed = results.iter().filter(|(_,ok,_)| !ok).count();
+    if format == Format::Json {
+        return if failed > 0 { Err(err!("{} of the days failed to run", failed)) } else { Ok(()) }
+    }
+
+    let ms = |d: Duration| format!("{:.1}ms", d.as_secs_f64() * 1000.0);
+    let part = |stars: &[Star], n: usize| match stars.iter().find(|s| s.part == Some(n)) {
+        Some(star) => ms(star.elapsed),
+        None => "-".to_owned()
+    };
+
+    println!("{:>4} | {:>12} | {:>12} | {:>12}", "Day", "Part 1", "Part 2", "Total");
+    println!("{:-<5}+{:-<14}+{:-<14}+{:-<13}", "", "", "", "");
+    let mut total = Duration::default();
+    for (n, ok, stars) in &results {
+        if !ok {
+            println!("{:>4} | {:>12} | {:>12} | {:>12}", n, "failed", "failed", "-");
+            continue
+        }
+        let day_total = stars.iter().map(|s| s.elapsed).sum();
+        total += day_total;
+        println!("{:>4} | {:>12} | {:>12} | {:>12}", n, part(stars, 1), part(stars, 2), ms(day_total));
+    }
+    println!("{:-<5}+{:-<14}+{:-<14}+{:-<13}", "", "", "", "");
+    println!("{:>4} | {:>12} | {:>12} | {:>12}", "", "", "", ms(total));
+
+    if failed > 0 {
+        Err(err!("{} of the days failed to run", failed))
+    } else {
+        Ok(())
+    }
+}
+
+/// What a panic was about, if it said.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "no message"
+    }
+}
+
+/// Run days with their default input, and compare the answers
+/// they give with those that we expect.
+fn verify(days: &[Box<dyn Day>], input_dir: &Path, matches: &ArgMatches) -> Result<(),Error> {
+    let expected = Expected::from_file(matches.value_of("answers").unwrap_or("answers.toml"))?;
+    let only = day_numbers(matches)?;
+
+    // Pictures look better on lines of their own:
+    let show = |s: &str| if s.contains('\n') { format!("\n{}", s) } else { s.to_owned() };
+
+    let mut correct = 0;
+    let mut incorrect = 0;
+    for day in days.iter().filter(|day| only.is_empty() || only.contains(&day.number())) {
+        let n = day.number();
+        let mut stars = Stars::new(|_| {});
+        if let Err(e) = day.run_default(input_dir, &mut stars) {
+            println!("{}: failed: {}", day.name(), e);
+            incorrect += expected.parts(n).count().max(1);
+            continue
+        }
+
+        let mut parts: Vec<usize> = expected
+            .parts(n)
+            .chain(stars.stars().iter().filter_map(|s| s.part))
+            .collect();
+        parts.sort_unstable();
+        parts.dedup();
+
+        for part in parts {
+            let actual = stars
+                .stars()
+                .iter()
+                .find(|s| s.part == Some(part))
+                .map(|s| s.answer.to_string());
+            let problem = match (expected.get(n, part), actual) {
+                (Some(e), Some(a)) if e == a => None,
+                (Some(e), Some(a)) => Some(format!("expected {} but got {}", show(e), show(&a))),
+                (Some(e), None) => Some(format!("expected {} but there was no answer", show(e))),
+                (None, Some(a)) => Some(format!("there is no expected answer, but got {}", show(&a))),
+                (None, None) => None
+            };
+            match problem {
+                None => {
+                    correct += 1;
+                    println!("{} part {}: ok", day.name(), part);
+                },
+                Some(problem) => {
+                    incorrect += 1;
+                    println!("{} part {}: {}", day.name(), part, problem);
+                }
+            }
+        }
+    }
+
+    println!("{} of {} answers are correct", correct, correct + incorrect);
+    if incorrect > 0 {
+        Err(err!("{} answers are incorrect", incorrect))
+    } else {
+        Ok(())
+    }
+}
+
+/// The days given as arguments to a subcommand.
+fn day_numbers(matches: &ArgMatches) -> Result<Vec<usize>,Error> {
+    matches
+        .values_of("DAY")
+        .into_iter()
+        .flatten()
+        .map(|n| n.parse().map_err(|_| err!("'{}' is not a day", n)))
+        .collect()
+}
+
+fn description_path(day: usize) -> String {
+    format!("descriptions/day{:02}.txt", day)
+}
+
+/// Print a day's description, wrapped to fit the terminal.
+fn describe(matches: &ArgMatches) -> Result<(),Error> {
+    let day = day_numbers(matches)?[0];
+    let width = matches
+        .value_of("width")
+        .unwrap_or("80")
+        .parse()
+        .map_err(|_| err!("The width should be a number of columns"))?;
+    let description = Description::from_file(description_path(day))?;
+    print!("{}", description.render(width));
+    Ok(())
+}
+
+/// Find the examples in days' descriptions, and print them or
+/// write them out ready for checking.
+fn examples(days: &[Box<dyn Day>], matches: &ArgMatches) -> Result<(),Error> {
+    let only = day_numbers(matches)?;
+    for day in days.iter().filter(|day| only.is_empty() || only.contains(&day.number())) {
+        let description = Description::from_file(description_path(day.number()))?;
+        let examples = Examples(description.examples());
+        match matches.value_of("out") {
+            Some(dir) => {
+                let path = Path::new(dir).join(format!("day{:02}.toml", day.number()));
+                std::fs::write(&path, examples.to_toml())?;
+                println!("{}: {} examples written to {}", day.name(), examples.0.len(), path.display());
+            },
+            None => {
+                println!("# {}\n{}", day.name(), examples.to_toml());
+            }
+        }
     }
+    Ok(())
 }
 
-/// A convenience function to read from a file.
-fn read(path: PathBuf) -> Result<String,Error> {
-    Ok(std::fs::read_to_string(path)?)
-}
\ No newline at end of file
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use advent_of_code_2019::support::Image;
+
+    #[test]
+    fn answers_as_json() {
+        assert_eq!(answer_json(&Answer::from(42usize)), json!(42));
+        assert_eq!(answer_json(&Answer::from(u64::MAX)), json!("18446744073709551615"));
+        assert_eq!(answer_json(&Answer::from("01234567")), json!("01234567"));
+        let image = Image::from_fn(3, 2, |x,y| x == y);
+        assert_eq!(answer_json(&image.into()), json!(["#  ", " # "]));
+    }
+
+}
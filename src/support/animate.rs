@@ -0,0 +1,140 @@
+use std::io::{ self, Write };
+use std::path::{ Path, PathBuf };
+use std::cell::RefCell;
+use std::time::{ Duration, Instant };
+use crate::error::Error;
+
+thread_local! {
+    // Kept per thread, like the target that pictures are rendered to:
+    static ANIMATION: RefCell<Option<Animation>> = const { RefCell::new(None) };
+}
+
+struct Animation {
+    output: Output,
+    every: usize,
+    seen: usize,
+    shown: usize,
+    last_shown: Option<Instant>
+}
+
+enum Output {
+    /// Redraw frames over each other on stderr, waiting this long between them.
+    Terminal(Duration),
+    /// Write each frame to its own file in this folder.
+    Folder(PathBuf)
+}
+
+/// A frame that's due to be shown.
+enum Frame {
+    /// Draw it on stderr after waiting this long.
+    Terminal { wait: Duration, drawn: String },
+    /// Write it to this file.
+    File { path: PathBuf, drawn: String }
+}
+
+/// Show frames drawn on this thread in the terminal from now on, at most `fps` a second
+/// (or as quickly as they come if that's 0). Only every `every`th frame
+/// is shown, so that long simulations can be sped up.
+pub fn animate_in_terminal(fps: f64, every: usize) -> Result<(),Error> {
+    if fps < 0.0 || !fps.is_finite() {
+        return Err(err!("The frame rate should be a number of frames a second"))
+    }
+    let delay = if fps == 0.0 { Duration::default() } else { Duration::from_secs_f64(1.0 / fps) };
+    start(Output::Terminal(delay), every)
+}
+
+/// Write frames to `frame-00001.txt`, `frame-00002.txt` and so on in
+/// a folder from now on, creating it if need be. Only every `every`th
+/// frame is written.
+pub fn dump_frames_to<P: AsRef<Path>>(dir: P, every: usize) -> Result<(),Error> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)
+        .map_err(|e| io::Error::new(e.kind(), format!("Cannot create folder '{}' for frames: {}", dir.display(), e)))?;
+    start(Output::Folder(dir.to_owned()), every)
+}
+
+fn start(output: Output, every: usize) -> Result<(),Error> {
+    if every == 0 {
+        return Err(err!("Frames to skip should be given as 1 (show every frame) or more"))
+    }
+    ANIMATION.with(|a| *a.borrow_mut() = Some(Animation { output, every, seen: 0, shown: 0, last_shown: None }));
+    Ok(())
+}
+
+/// Hand over the next frame of a simulation, which is only drawn (by
+/// calling the function given) if it's going to be shown.
+pub fn frame<F: FnOnce() -> String>(draw: F) -> Result<(),Error> {
+    // Work out what to do first, so that nothing's held on to
+    // while waiting for the next frame to be due:
+    let next = ANIMATION.with(|animation| {
+        let mut animation = animation.borrow_mut();
+        let animation = match &mut *animation {
+            Some(animation) => animation,
+            None => return None
+        };
+        animation.seen += 1;
+        if (animation.seen - 1) % animation.every != 0 {
+            return None
+        }
+        animation.shown += 1;
+        let drawn = draw();
+        let now = Instant::now();
+        match &animation.output {
+            Output::Terminal(delay) => {
+                let wait = animation.last_shown
+                    .map(|last| delay.checked_sub(now - last).unwrap_or_default())
+                    .unwrap_or_default();
+                animation.last_shown = Some(now + wait);
+                Some(Frame::Terminal { wait, drawn })
+            },
+            Output::Folder(dir) => {
+                animation.last_shown = Some(now);
+                Some(Frame::File { path: dir.join(format!("frame-{:05}.txt", animation.shown)), drawn })
+            }
+        }
+    });
+    match next {
+        None => {},
+        Some(Frame::Terminal { wait, drawn }) => {
+            std::thread::sleep(wait);
+            // Move to the top left and clear the screen before drawing:
+            let stderr = io::stderr();
+            let mut stderr = stderr.lock();
+            write!(stderr, "\x1b[H\x1b[2J{}", drawn)?;
+            stderr.flush()?;
+        },
+        Some(Frame::File { path, drawn }) => {
+            std::fs::write(&path, drawn)
+                .map_err(|e| io::Error::new(e.kind(), format!("Cannot write frame to '{}': {}", path.display(), e)))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    // The animation is kept per thread, so everything that sets
+    // it up is tested here, together:
+    #[test]
+    fn dumps_every_nth_frame() {
+        let dir = std::env::temp_dir().join(format!("aoc2019-frames-{}", std::process::id()));
+        let mut drawn = 0;
+        frame(|| { drawn += 1; String::new() }).unwrap();
+        assert_eq!(drawn, 0);
+
+        dump_frames_to(&dir, 2).unwrap();
+        for n in 0..5 {
+            frame(|| { drawn += 1; format!("frame {}", n) }).unwrap();
+        }
+        ANIMATION.with(|a| *a.borrow_mut() = None);
+
+        assert_eq!(drawn, 3);
+        assert_eq!(std::fs::read_to_string(dir.join("frame-00002.txt")).unwrap(), "frame 2");
+        assert!(!dir.join("frame-00004.txt").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+}
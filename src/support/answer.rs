@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// The answer to one part of a day. Most answers are numbers, but some
+/// are strings of digits (which may start with a 0) or pictures.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    Image(Image)
+}
+
+macro_rules! number_from {
+    ($($ty:ty)*) => {$(
+        impl From<$ty> for Answer {
+            fn from(n: $ty) -> Answer {
+                Answer::Number(n as i128)
+            }
+        }
+    )*}
+}
+number_from!(i32 i64 i128 u32 u64 u128 usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(s.to_owned())
+    }
+}
+
+impl From<Image> for Answer {
+    fn from(image: Image) -> Answer {
+        Answer::Image(image)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Image(image) => write!(f, "{}", image)
+        }
+    }
+}
+
+/// A picture made up of pixels that are either lit or not.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Image {
+    width: usize,
+    pixels: Vec<bool>
+}
+
+impl Image {
+
+    /// An image from pixels given row by row, `width` to a row.
+    pub fn new(width: usize, pixels: Vec<bool>) -> Image {
+        assert!(width > 0 && pixels.len().is_multiple_of(width), "pixels should fill every row of the image");
+        Image { width, pixels }
+    }
+
+    /// An image that's `width` by `height`, lighting the pixels
+    /// for which the function returns true.
+    pub fn from_fn<F: FnMut(usize,usize) -> bool>(width: usize, height: usize, mut f: F) -> Image {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x,y)))
+            .map(|(x,y)| f(x,y))
+            .collect();
+        Image::new(width, pixels)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item=&[bool]> {
+        self.pixels.chunks_exact(self.width)
+    }
+
+}
+
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            let line: String = row.iter().map(|&lit| if lit { '#' } else { ' ' }).collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn displays_answers() {
+        assert_eq!(Answer::from(42usize).to_string(), "42");
+        assert_eq!(Answer::from("01234567").to_string(), "01234567");
+
+        let image = Image::from_fn(4, 2, |x,y| x == y || x == 3);
+        assert_eq!(Answer::from(image).to_string(), "#  #\n # #\n");
+    }
+
+}
@@ -0,0 +1,129 @@
+use crate::error::Error;
+use crate::support::grid::{ Point, SparseGrid };
+
+/// What to do with whitespace at the start and end of each line of a map.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Whitespace {
+    /// Keep it, for maps where spaces mean something (like day 20's,
+    /// where portal labels line up with the maze).
+    Keep,
+    /// Strip it, so that maps can be indented.
+    Strip
+}
+
+/// A map drawn with characters, like the mazes and asteroid fields in
+/// the puzzles, with each character turned into something more useful.
+/// The top left character is at (0,0).
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct AsciiMap<T> {
+    cells: SparseGrid<T>,
+    width: usize,
+    height: usize,
+    // The line number and indent of each row, for pointing errors at the input:
+    rows: Vec<(usize,usize)>
+}
+
+impl <T> AsciiMap<T> {
+
+    /// Parse a map, turning each character into a `T` with the function
+    /// given, which hands back None for characters that shouldn't be on
+    /// the map; the first of those is reported along with where it is.
+    /// Blank lines before and after the map are ignored.
+    pub fn parse<F: FnMut(char) -> Option<T>>(input: &str, whitespace: Whitespace, mut f: F) -> Result<AsciiMap<T>,Error> {
+        let is_blank = |line: &str| match whitespace {
+            Whitespace::Keep => line.is_empty(),
+            Whitespace::Strip => line.trim().is_empty()
+        };
+        let lines: Vec<(usize,&str)> = input
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .enumerate()
+            .skip_while(|&(_,line)| is_blank(line))
+            .collect();
+        let len = lines.iter().rposition(|&(_,line)| !is_blank(line)).map(|n| n + 1).unwrap_or(0);
+
+        let mut cells = SparseGrid::new();
+        let mut width = 0;
+        let mut rows = vec![];
+        for (y, &(idx, line)) in lines[..len].iter().enumerate() {
+            let (indent, line) = match whitespace {
+                Whitespace::Keep => (0, line),
+                Whitespace::Strip => {
+                    let trimmed = line.trim_start();
+                    (line.chars().count() - trimmed.chars().count(), trimmed.trim_end())
+                }
+            };
+            for (x, c) in line.chars().enumerate() {
+                let value = f(c).ok_or_else(|| Error::parse(idx+1, indent+x+1, format!("Unexpected character '{}' in map", c)))?;
+                cells.insert(Point::new(x as i64, y as i64), value);
+            }
+            width = width.max(line.chars().count());
+            rows.push((idx+1, indent));
+        }
+        Ok(AsciiMap { cells, width, height: len, rows })
+    }
+
+    /// The length of the longest line.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of lines.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// What's at some point, or None if that's past the end of its line
+    /// or outside of the map.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(p)
+    }
+
+    /// Every point on the map along with what's there, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item=(Point,&T)> {
+        self.cells.iter()
+    }
+
+    /// An error pointing at some point on the map, where it was in the input.
+    pub fn error_at<S: Into<String>>(&self, p: Point, message: S) -> Error {
+        let (line, indent) = self.rows.get(p.y as usize).copied().unwrap_or((1, 0));
+        Error::parse(line, indent + p.x as usize + 1, message)
+    }
+
+    pub fn into_grid(self) -> SparseGrid<T> {
+        self.cells
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn parses_maps() {
+        let map = AsciiMap::parse("\n  #.\n  .##\n\n", Whitespace::Strip, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None
+        }).unwrap();
+        assert_eq!((map.width(), map.height()), (3, 2));
+        assert_eq!(map.get(Point::new(2, 1)), Some(&true));
+        assert_eq!(map.get(Point::new(2, 0)), None);
+
+        let map = AsciiMap::parse("  A\n#.#", Whitespace::Keep, Some).unwrap();
+        assert_eq!(map.get(Point::new(2, 0)), Some(&'A'));
+        assert_eq!(map.get(Point::new(0, 0)), Some(&' '));
+    }
+
+    #[test]
+    fn says_where_unknown_characters_are() {
+        let err = AsciiMap::parse("\n #.\n #?#", Whitespace::Strip, |c| if c == '?' { None } else { Some(c) }).unwrap_err();
+        assert_eq!(err.to_string(), "line 3, column 3: Unexpected character '?' in map");
+
+        let map = AsciiMap::parse("\n #.\n #!#", Whitespace::Strip, Some).unwrap();
+        assert_eq!(map.error_at(Point::new(1, 1), "Bang").to_string(), "line 3, column 3: Bang");
+    }
+
+}
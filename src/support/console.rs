@@ -0,0 +1,372 @@
+use std::cell::RefCell;
+use std::collections::{ BTreeSet, VecDeque };
+use std::fs::File;
+use std::io::{ self, Read, Write, stdout };
+use std::rc::Rc;
+use std::str::FromStr;
+use rustyline::{ Editor, Context, Helper };
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use crate::error::Error;
+use crate::support::intcode::Intcode;
+use crate::support::terminal::{ Terminal, NonAscii, Snapshot };
+
+/// A line-oriented front end to a `Terminal` for playing ASCII Intcode
+/// programs by hand. Lines can be edited, and there is history and tab
+/// completion of words and list items the program has printed. Lines
+/// starting with a `:` are handled here rather than sent to the machine.
+pub struct Console {
+    terminal: Terminal<Pending,Output>,
+    editor: Editor<Words>,
+    pending: Pending,
+    // Each line sent so far, and the terminal as it was just before it:
+    sent: Vec<(String,Snapshot)>,
+    uppercase: bool
+}
+
+impl FromStr for Console {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Console,Error> {
+        let intcode = Intcode::from_str(input)?;
+        Ok(Console::new(intcode))
+    }
+}
+
+impl Console {
+
+    pub fn new(intcode: Intcode) -> Console {
+        let words = Words::default();
+        let pending = Pending::default();
+        let output = Output { words: words.clone(), line: vec![] };
+        let mut editor = Editor::new();
+        editor.set_helper(Some(words));
+        Console {
+            terminal: Terminal::new(intcode, pending.clone(), output),
+            editor,
+            pending,
+            sent: vec![],
+            uppercase: false
+        }
+    }
+
+    /// Convert lines to ASCII uppercase before sending them.
+    pub fn uppercase(mut self) -> Console {
+        self.uppercase = true;
+        self
+    }
+
+    /// Decide what to do with non ASCII values; see `Terminal::non_ascii`.
+    pub fn non_ascii(mut self, non_ascii: NonAscii) -> Console {
+        self.terminal = self.terminal.non_ascii(non_ascii);
+        self
+    }
+
+    /// Record a transcript of the session; see `Terminal::record`.
+    pub fn record<T: Write + 'static>(&mut self, writer: T) {
+        self.terminal.record(writer);
+    }
+
+    /// Run the program until it finishes or the user quits, handing
+    /// back every non ASCII value that it output along the way.
+    pub fn run(&mut self) -> Result<&[i64],Error> {
+        while self.step()?.is_some() {}
+        Ok(self.terminal.values())
+    }
+
+    /// Run the program, prompting for a line whenever it wants input,
+    /// until a non ASCII value is handed back, the program finishes or
+    /// the user quits.
+    pub fn step(&mut self) -> Result<Option<i64>,Error> {
+        loop {
+            match self.terminal.step() {
+                Ok(out) => return Ok(out),
+                Err(e) => {
+                    if e.io_kind() != Some(io::ErrorKind::WouldBlock) {
+                        return Err(e)
+                    }
+                }
+            }
+            // The machine is waiting on a line from us:
+            if !self.prompt()? {
+                return Ok(None)
+            }
+        }
+    }
+
+    /// Ask for lines until one is sent to the machine (returning true)
+    /// or the user asks to quit (returning false).
+    fn prompt(&mut self) -> Result<bool,Error> {
+        loop {
+            let line = match self.editor.readline("> ") {
+                Ok(line) => line,
+                Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => return Ok(false),
+                Err(e) => return Err(err!("Cannot read a line: {}", e))
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue
+            }
+            self.editor.add_history_entry(line);
+            if let Some(go_on) = self.enter(line)? {
+                return Ok(go_on)
+            }
+        }
+    }
+
+    /// Send a line to the machine, or carry out a command if it starts
+    /// with a `:`. Hands back true if the machine has something new to
+    /// run with, false if the user asked to quit, and None if another
+    /// line is needed.
+    fn enter(&mut self, line: &str) -> Result<Option<bool>,Error> {
+        if !line.starts_with(':') {
+            self.send(line);
+            return Ok(Some(true))
+        }
+
+        let mut words = line[1..].splitn(2, ' ');
+        let command = words.next().unwrap_or("");
+        let arg = words.next().map(|s| s.trim()).unwrap_or("");
+        match command {
+            "quit" | "q" => {
+                return Ok(Some(false))
+            },
+            "undo" | "u" => {
+                if let Some((line, snapshot)) = self.sent.pop() {
+                    self.pending.0.borrow_mut().clear();
+                    self.terminal.restore(snapshot)?;
+                    println!("(undid '{}')", line);
+                    return Ok(Some(true))
+                } else {
+                    println!("(nothing to undo)");
+                }
+            },
+            "save" | "s" if !arg.is_empty() => {
+                let mut file = File::create(arg)?;
+                for (line, _) in &self.sent {
+                    writeln!(file, "{}", line)?;
+                }
+                println!("(saved {} lines to {})", self.sent.len(), arg);
+            },
+            "history" | "h" => {
+                for (idx, (line, _)) in self.sent.iter().enumerate() {
+                    println!("{:>4}  {}", idx+1, line);
+                }
+            },
+            _ => {
+                println!("(commands: ':undo', ':save FILE', ':history', ':quit')");
+            }
+        }
+        Ok(None)
+    }
+
+    fn send(&mut self, line: &str) {
+        let line = if self.uppercase { line.to_ascii_uppercase() } else { line.to_owned() };
+        let mut pending = self.pending.0.borrow_mut();
+        pending.extend(line.as_bytes());
+        pending.push_back(b'\n');
+        self.sent.push((line, self.terminal.snapshot()));
+    }
+
+}
+
+/// Bytes waiting to be handed to the machine. Reading when there are
+/// none gives back a `WouldBlock` error rather than waiting.
+#[derive(Clone,Default)]
+struct Pending(Rc<RefCell<VecDeque<u8>>>);
+
+impl Read for Pending {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut pending = self.0.borrow_mut();
+        if pending.is_empty() {
+            return Err(io::ErrorKind::WouldBlock.into())
+        }
+        let n = buf.len().min(pending.len());
+        for (b, p) in buf.iter_mut().zip(pending.drain(..n)) {
+            *b = p;
+        }
+        Ok(n)
+    }
+}
+
+/// Write program output to stdout, noting the words in each line
+/// so that they can be tab completed.
+struct Output {
+    words: Words,
+    line: Vec<u8>
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &b in buf {
+            if b == b'\n' {
+                self.words.add_line(&String::from_utf8_lossy(&self.line));
+                self.line.clear();
+            } else {
+                self.line.push(b);
+            }
+        }
+        let stdout = stdout();
+        let mut stdout = stdout.lock();
+        stdout.write_all(buf)?;
+        stdout.flush()?;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        stdout().flush()
+    }
+}
+
+/// Words and list items (lines like "- astronaut ice cream") seen in
+/// the program output, used to complete the word being typed.
+#[derive(Clone,Default)]
+struct Words(Rc<RefCell<BTreeSet<String>>>);
+
+impl Words {
+    fn add_line(&self, line: &str) {
+        let mut words = self.0.borrow_mut();
+        if let Some(item) = line.trim().strip_prefix("- ") {
+            words.insert(item.trim().to_owned());
+        }
+        for word in line.split(|c: char| !c.is_ascii_alphanumeric() && c != '-').filter(|w| w.len() > 1) {
+            words.insert(word.to_owned());
+        }
+    }
+}
+
+impl Completer for Words {
+    type Candidate = String;
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let words = self.0.borrow();
+        // Try to complete as much of the line as possible first, so that
+        // "take ast" can complete to a multi-word item:
+        let starts = std::iter::once(0).chain(line.match_indices(' ').map(|(n,_)| n+1));
+        for start in starts {
+            let partial = &line[start..];
+            if partial.is_empty() {
+                continue
+            }
+            let candidates: Vec<String> = words
+                .range(partial.to_owned()..)
+                .take_while(|w| w.starts_with(partial))
+                .cloned()
+                .collect();
+            if !candidates.is_empty() {
+                return Ok((start, candidates))
+            }
+        }
+        Ok((pos, vec![]))
+    }
+}
+
+impl Hinter for Words {
+    type Hint = String;
+}
+
+impl Highlighter for Words {}
+
+impl Validator for Words {}
+
+impl Helper for Words {}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use rustyline::history::History;
+    use crate::support::transcript::{ Entry, parse_transcript };
+
+    // Hand back each character read plus 1000, forever:
+    static PLUS_1000: &str = "3,100,1001,100,1000,101,4,101,1105,1,0";
+
+    // Run until the machine wants more input than it's been given:
+    fn run(console: &mut Console) {
+        let e = console.terminal.step().unwrap_err();
+        assert_eq!(e.io_kind(), Some(io::ErrorKind::WouldBlock));
+    }
+
+    #[test]
+    fn undo_rolls_back_machine_values_and_transcript() {
+        let dir = std::env::temp_dir().join(format!("aoc2019-console-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut console = Console::from_str(PLUS_1000).unwrap().non_ascii(NonAscii::Collect);
+        console.record(File::create(dir.join("transcript.txt")).unwrap());
+        run(&mut console);
+
+        assert_eq!(console.enter("a").unwrap(), Some(true));
+        run(&mut console);
+        assert_eq!(console.enter("b").unwrap(), Some(true));
+        run(&mut console);
+        assert_eq!(console.terminal.values(), &[1097, 1010, 1098, 1010]);
+
+        assert_eq!(console.enter(":undo").unwrap(), Some(true));
+        run(&mut console);
+        assert_eq!(console.terminal.values(), &[1097, 1010]);
+        assert_eq!(console.enter("c").unwrap(), Some(true));
+        run(&mut console);
+        assert_eq!(console.terminal.values(), &[1097, 1010, 1099, 1010]);
+
+        let entries = vec![
+            Entry::Value(1097),
+            Entry::Input("a".to_owned()),
+            Entry::Value(1010),
+            Entry::Value(1099),
+            Entry::Input("c".to_owned()),
+            Entry::Value(1010)
+        ];
+        assert_eq!(console.terminal.transcript().unwrap().entries(), &*entries);
+        let written = std::fs::read_to_string(dir.join("transcript.txt")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(parse_transcript(&written).unwrap(), entries);
+    }
+
+    #[test]
+    fn meta_commands() {
+        let dir = std::env::temp_dir().join(format!("aoc2019-console-meta-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut console = Console::from_str(PLUS_1000).unwrap().non_ascii(NonAscii::Collect);
+        run(&mut console);
+
+        assert_eq!(console.enter(":undo").unwrap(), None);
+        assert_eq!(console.enter("north").unwrap(), Some(true));
+        run(&mut console);
+        assert_eq!(console.enter("take map").unwrap(), Some(true));
+        run(&mut console);
+        assert_eq!(console.enter(":history").unwrap(), None);
+        assert_eq!(console.enter(":nonsense").unwrap(), None);
+
+        let path = dir.join("saved.txt");
+        assert_eq!(console.enter(&format!(":save {}", path.display())).unwrap(), None);
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(saved, "north\ntake map\n");
+
+        assert_eq!(console.enter(":q").unwrap(), Some(false));
+    }
+
+    #[test]
+    fn completes_words_and_items() {
+        let words = Words::default();
+        words.add_line("Items here:");
+        words.add_line("- astronaut ice cream");
+        words.add_line("- antenna");
+
+        let history = History::new();
+        let ctx = Context::new(&history);
+
+        let (start, candidates) = words.complete("take ast", 8, &ctx).unwrap();
+        assert_eq!(start, 5);
+        assert_eq!(candidates, vec!["astronaut", "astronaut ice cream"]);
+
+        let (start, candidates) = words.complete("It", 2, &ctx).unwrap();
+        assert_eq!(start, 0);
+        assert_eq!(candidates, vec!["Items"]);
+
+        let (_, candidates) = words.complete("take x", 6, &ctx).unwrap();
+        assert!(candidates.is_empty());
+    }
+
+}
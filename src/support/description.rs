@@ -0,0 +1,360 @@
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::error::Error;
+use crate::support::examples::Example;
+
+/// A puzzle description, as found in `descriptions/dayNN.txt`, broken
+/// into blocks of text. Blocks are separated by blank lines, and are
+/// either paragraphs, lists (whose items are indented by 4 spaces), or
+/// preformatted text like maps and example inputs.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Description {
+    pub title: String,
+    /// Each block, along with the part of the puzzle it's in.
+    pub blocks: Vec<(usize,Block)>
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum Block {
+    Heading(String),
+    Prose(String),
+    List(Vec<String>),
+    Pre(String)
+}
+
+impl FromStr for Description {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Description,Error> {
+        let mut title = None;
+        let mut part = 1;
+        let mut blocks = vec![];
+        for lines in split_blocks(input) {
+            let block = classify(&lines);
+            if let Block::Heading(heading) = &block {
+                if title.is_none() {
+                    title = Some(heading.clone());
+                } else {
+                    part += 1;
+                }
+            }
+            blocks.push((part, block));
+        }
+        let title = title.ok_or_else(|| Error::parse_somewhere("Expected a heading like '--- Day 1: Title ---'"))?;
+        Ok(Description { title, blocks })
+    }
+}
+
+impl Description {
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Description,Error> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("Cannot read description '{}': {}", path.display(), e)))?;
+        Description::from_str(&input).map_err(|e| e.in_file(path.display().to_string()))
+    }
+
+    /// The description laid out for reading in a terminal `width` columns wide.
+    /// Paragraphs and list items are wrapped, and preformatted text is indented.
+    pub fn render(&self, width: usize) -> String {
+        let mut out = String::new();
+        for (_, block) in &self.blocks {
+            match block {
+                Block::Heading(heading) => {
+                    out.push_str(heading);
+                    out.push('\n');
+                    let underline = if heading == &self.title { '=' } else { '-' };
+                    out.extend(std::iter::repeat_n(underline, heading.chars().count()));
+                    out.push('\n');
+                },
+                Block::Prose(text) => {
+                    out.push_str(&wrap(text, width, "", ""));
+                },
+                Block::List(items) => {
+                    for item in items {
+                        out.push_str(&wrap(item, width, "  * ", "    "));
+                    }
+                },
+                Block::Pre(text) => {
+                    for line in text.lines() {
+                        out.push_str("    ");
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Pick out what look like worked examples: inputs given alongside the
+    /// answer they lead to. This is guesswork, so the examples found need
+    /// checking by hand before they're relied on. Two shapes are spotted:
+    ///
+    /// - List items like "INPUT becomes ANSWER." or "INPUT = distance ANSWER",
+    ///   where the input may span the items before it, or like "For a mass
+    ///   of INPUT, the fuel required is ANSWER."
+    /// - Preformatted inputs, with the answer at the end of a sentence after
+    ///   them that ends in a number (preferring one about "this example").
+    ///   Pictures of the input drawn just after it ("the above map looks
+    ///   like this") or spread over several blocks are skipped, and so is
+    ///   anything after the description turns to the reader's own input.
+    pub fn examples(&self) -> Vec<Example> {
+        let mut examples = vec![];
+        for (idx, (part, block)) in self.blocks.iter().enumerate() {
+            let part = *part;
+            if self.is_redrawing(idx) || self.is_part_of_picture(idx) {
+                continue
+            }
+            match block {
+                Block::List(items) => {
+                    let mut pending: Vec<&str> = vec![];
+                    for item in items {
+                        match list_example(item).or_else(|| worded_example(item)) {
+                            Some((input, answer)) => {
+                                pending.push(input);
+                                examples.push(Example::new(part, pending.join("\n"), answer));
+                                pending.clear();
+                            },
+                            // Items without spaces are likely input that
+                            // continues on to the next item:
+                            None if !item.contains(' ') => pending.push(item),
+                            None => pending.clear()
+                        }
+                    }
+                },
+                Block::Pre(input) => {
+                    let sentences: Vec<&str> = self.blocks[idx+1..]
+                        .iter()
+                        .enumerate()
+                        .filter(|&(n, _)| !self.is_redrawing(idx+1+n))
+                        .take_while(|(_, (p, b))| *p == part && !matches!(b, Block::Pre(_) | Block::Heading(_)))
+                        .flat_map(|(_, (_, b))| match b {
+                            Block::Prose(text) => vec![text.as_str()],
+                            Block::List(items) => items.iter().map(|s| s.as_str()).collect(),
+                            _ => vec![]
+                        })
+                        .flat_map(sentences)
+                        .take_while(|sentence| !is_about_own_input(sentence))
+                        .collect();
+                    let answer = sentences.iter()
+                        .filter(|sentence| sentence.contains("example"))
+                        .chain(&sentences)
+                        .find_map(|sentence| answer_at_end(sentence));
+                    if let Some(answer) = answer {
+                        examples.push(Example::new(part, input.clone(), answer));
+                    }
+                },
+                _ => {}
+            }
+        }
+        examples
+    }
+
+    /// Is the block at `idx` preformatted text right next to more of it,
+    /// like day 8's image layers? Those are one picture between them,
+    /// rather than an input.
+    fn is_part_of_picture(&self, idx: usize) -> bool {
+        let is_pre = |n: usize| matches!(self.blocks.get(n), Some((_, Block::Pre(_))));
+        is_pre(idx) && ((idx > 0 && is_pre(idx-1)) || is_pre(idx+1))
+    }
+
+    /// Is the block at `idx` a picture of the input just before it,
+    /// introduced with something like "the above map looks like this"?
+    fn is_redrawing(&self, idx: usize) -> bool {
+        let introduced_as_redrawing = idx > 0 && match &self.blocks[idx-1].1 {
+            Block::Prose(text) => text.contains("the above"),
+            _ => false
+        };
+        introduced_as_redrawing && matches!(self.blocks[idx].1, Block::Pre(_))
+    }
+
+}
+
+fn split_blocks(input: &str) -> Vec<Vec<&str>> {
+    let mut blocks = vec![];
+    let mut current = vec![];
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+fn classify(lines: &[&str]) -> Block {
+    static HEADING: Lazy<Regex> = Lazy::new(|| Regex::new(r"^--- (.+) ---$").unwrap());
+    let is_item = |line: &&str| line.starts_with("    ") && !line[4..].starts_with(' ');
+    if lines.len() == 1 {
+        if let Some(caps) = HEADING.captures(lines[0]) {
+            return Block::Heading(caps[1].to_owned())
+        }
+    }
+    if lines.iter().all(is_item) {
+        Block::List(lines.iter().map(|line| line.trim().to_owned()).collect())
+    } else if lines.len() == 1 && looks_like_prose(lines[0]) {
+        Block::Prose(lines[0].to_owned())
+    } else {
+        Block::Pre(lines.join("\n"))
+    }
+}
+
+fn looks_like_prose(line: &str) -> bool {
+    let ends_sentence = line.ends_with(|c| ".:?!\"".contains(c));
+    !line.starts_with(' ') && line.contains(' ') && (ends_sentence || line.split_whitespace().count() >= 5)
+}
+
+/// Wrap text to a width, starting the first line with one prefix
+/// and the rest with another.
+fn wrap(text: &str, width: usize, first: &str, rest: &str) -> String {
+    let mut out = String::new();
+    let mut line = first.to_owned();
+    let mut empty = true;
+    for word in text.split_whitespace() {
+        if !empty && line.chars().count() + 1 + word.chars().count() > width {
+            out.push_str(&line);
+            out.push('\n');
+            line = rest.to_owned();
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+fn sentences(text: &str) -> Vec<&str> {
+    static END: Lazy<Regex> = Lazy::new(|| Regex::new(r"[.!?:]\s+").unwrap());
+    END.split(text).collect()
+}
+
+/// "1969 becomes 654." or "R8,U5 = distance 159"
+fn list_example(item: &str) -> Option<(&str,String)> {
+    static BECOMES: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\S+) becomes (\S+?)[.,]?(\s|$)").unwrap());
+    static EQUALS: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\S+) = \D*?(-?\d+)\D*$").unwrap());
+    BECOMES.captures(item)
+        .or_else(|| EQUALS.captures(item))
+        .map(|caps| (caps.get(1).unwrap().as_str(), caps[2].to_owned()))
+}
+
+/// "For a mass of 12, ... to get 2." or "A module of mass 14 requires ... just 2."
+fn worded_example(item: &str) -> Option<(&str,String)> {
+    static INPUT: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?:[Aa]n? [a-z]+ of|of [a-z]+) (-?\d+)\b").unwrap());
+    static NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+    // The answer has to be a different number to the input:
+    if NUMBER.find_iter(item).count() < 2 {
+        return None
+    }
+    let input = INPUT.captures(item)?.get(1).unwrap().as_str();
+    let answer = sentences(item).into_iter().rev().find(|s| !s.trim().is_empty()).and_then(answer_at_end)?;
+    Some((input, answer))
+}
+
+/// Sentences like "The image you received is 25 pixels wide" or "What
+/// is ... your map data?" are about the reader's puzzle input rather
+/// than an example.
+fn is_about_own_input(sentence: &str) -> bool {
+    sentence.contains("you received") || sentence.split_whitespace()
+        .any(|word| matches!(word.trim_matches(|c: char| !c.is_alphanumeric()), "your" | "Your"))
+}
+
+/// "... in this example is 42." or "... requires 31 ORE"
+fn answer_at_end(sentence: &str) -> Option<String> {
+    static END: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s(-?\d+)(\s[A-Za-z]+)?[.!]?$").unwrap());
+    END.captures(sentence.trim()).map(|caps| caps[1].to_owned())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    const DESCRIPTION: &str = "\
+--- Day 99: Test Day ---
+
+The first paragraph of the description, which is long enough to wrap.
+
+For example:
+
+    12 becomes 2.
+    R8,U5
+    U7,R6 = distance 6
+
+Suppose you have the following map:
+
+COM)B
+B)C
+
+The total number of orbits in this example is 3.
+
+--- Part Two ---
+
+Now do it all over again backwards.
+";
+
+    #[test]
+    fn splits_descriptions_into_blocks() {
+        let description = Description::from_str(DESCRIPTION).unwrap();
+        assert_eq!(description.title, "Day 99: Test Day");
+        assert_eq!(description.blocks[3], (1, Block::List(vec!["12 becomes 2.".to_owned(), "R8,U5".to_owned(), "U7,R6 = distance 6".to_owned()])));
+        assert_eq!(description.blocks[5], (1, Block::Pre("COM)B\nB)C".to_owned())));
+        assert_eq!(description.blocks[8], (2, Block::Prose("Now do it all over again backwards.".to_owned())));
+        assert!(description.render(40).starts_with("Day 99: Test Day\n================\n\nThe first paragraph of the description,\nwhich"));
+    }
+
+    #[test]
+    fn finds_examples() {
+        let description = Description::from_str(DESCRIPTION).unwrap();
+        let examples = description.examples();
+        assert_eq!(examples, vec![
+            Example::new(1, "12".to_owned(), "2".to_owned()),
+            Example::new(1, "R8,U5\nU7,R6".to_owned(), "6".to_owned()),
+            Example::new(1, "COM)B\nB)C".to_owned(), "3".to_owned())
+        ]);
+    }
+
+    #[test]
+    fn skips_pictures_and_the_readers_own_input() {
+        let description = Description::from_str("\
+--- Day 98: Pictures ---
+
+    For a mass of 12, the fuel required is 2.
+    D orbits C, a total of 3 orbits.
+
+COM)B
+B)C
+
+Visually, the above map of orbits looks like this:
+
+COM - B - C
+
+Here, B orbits 1 thing. The total in this example is 3.
+
+Layer 1: 12
+
+Layer 2: 00
+
+The image you received is 25 pixels wide. It has 0 layers.
+").unwrap();
+        assert_eq!(description.examples(), vec![
+            Example::new(1, "12".to_owned(), "2".to_owned()),
+            Example::new(1, "COM)B\nB)C".to_owned(), "3".to_owned())
+        ]);
+    }
+
+}
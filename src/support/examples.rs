@@ -0,0 +1,128 @@
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use toml::Value;
+use crate::error::Error;
+
+/// A worked example from a puzzle description: an input, and the answer
+/// to one part of the puzzle that it should lead to. Examples are kept in
+/// `examples/dayNN.toml`, like:
+///
+/// ```toml
+/// [[example]]
+/// part = 1
+/// input = '''
+/// COM)B
+/// B)C'''
+/// answer = "3"
+/// ```
+///
+/// An example can also give `args` to run the day with, for instance
+/// to use a smaller image size than the real puzzle.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Example {
+    pub part: usize,
+    pub input: String,
+    pub answer: String,
+    pub args: Vec<String>
+}
+
+impl Example {
+    pub fn new(part: usize, input: String, answer: String) -> Example {
+        Example { part, input, answer, args: vec![] }
+    }
+}
+
+/// The examples for one day.
+#[derive(Debug,Clone,PartialEq,Eq,Default)]
+pub struct Examples(pub Vec<Example>);
+
+impl FromStr for Examples {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Examples,Error> {
+        let value: Value = input.parse()?;
+        let examples = match value.get("example") {
+            Some(Value::Array(examples)) => examples,
+            Some(_) => return Err(err!("Expected 'example' to be an array of tables")),
+            None => return Ok(Examples::default())
+        };
+        let mut out = vec![];
+        for (idx, example) in examples.iter().enumerate() {
+            let field = |name: &str| example
+                .get(name)
+                .ok_or_else(|| err!("Example {} has no '{}'", idx+1, name));
+            let part = field("part")?
+                .as_integer()
+                .filter(|&n| n == 1 || n == 2)
+                .ok_or_else(|| err!("Example {} should be for part 1 or 2", idx+1))?;
+            let input = field("input")?
+                .as_str()
+                .ok_or_else(|| err!("Example {} should have a string input", idx+1))?;
+            let answer = match field("answer")? {
+                Value::Integer(n) => n.to_string(),
+                Value::String(s) => s.clone(),
+                _ => return Err(err!("Example {} should have a number or a string answer", idx+1))
+            };
+            let args = match example.get("args") {
+                None => vec![],
+                Some(args) => args
+                    .as_array()
+                    .and_then(|args| args.iter().map(|a| a.as_str().map(|s| s.to_owned())).collect())
+                    .ok_or_else(|| err!("Example {} should have a list of strings as args", idx+1))?
+            };
+            out.push(Example { part: part as usize, input: input.to_owned(), answer, args });
+        }
+        Ok(Examples(out))
+    }
+}
+
+impl Examples {
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Examples,Error> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("Cannot read examples '{}': {}", path.display(), e)))?;
+        Examples::from_str(&input).map_err(|e| e.in_file(path.display().to_string()))
+    }
+
+    /// The examples written out in the form that `from_str` reads. Inputs
+    /// are written as literal strings, so that they appear just as they do
+    /// in the description.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        for (idx, example) in self.0.iter().enumerate() {
+            if idx > 0 {
+                out.push('\n');
+            }
+            out.push_str("[[example]]\n");
+            out.push_str(&format!("part = {}\n", example.part));
+            if example.input.contains("'''") {
+                out.push_str(&format!("input = {}\n", Value::String(example.input.clone())));
+            } else {
+                out.push_str(&format!("input = '''\n{}'''\n", example.input));
+            }
+            out.push_str(&format!("answer = {}\n", Value::String(example.answer.clone())));
+            if !example.args.is_empty() {
+                let args: Vec<Value> = example.args.iter().map(|a| Value::String(a.clone())).collect();
+                out.push_str(&format!("args = {}\n", Value::Array(args)));
+            }
+        }
+        out
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn examples_round_trip() {
+        let mut example = Example::new(2, "COM)B\nB)C\\D".to_owned(), "3".to_owned());
+        example.args = vec!["--image-width".to_owned(), "3".to_owned()];
+        let examples = Examples(vec![Example::new(1, "12".to_owned(), "2".to_owned()), example]);
+        assert_eq!(Examples::from_str(&examples.to_toml()).unwrap(), examples);
+    }
+
+}
@@ -0,0 +1,114 @@
+use std::io;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use toml::Value;
+use crate::error::Error;
+
+/// The answers we expect each day to give, read from a TOML file with
+/// a table for each day and a key for each part, like:
+///
+/// ```toml
+/// [day1]
+/// part1 = 1234
+/// part2 = "01234567"
+/// ```
+///
+/// Answers that are pictures can be given as multi-line strings.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Expected {
+    // Answers as they would be displayed, by day and part:
+    answers: BTreeMap<(usize,usize),String>
+}
+
+impl FromStr for Expected {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Expected,Error> {
+        let value: Value = input.parse()?;
+        let mut answers = BTreeMap::new();
+        let days = value.as_table().ok_or_else(|| err!("Expected a table of days"))?;
+        for (day_key, parts) in days {
+            let day = parse_key(day_key, "day")?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| err!("Expected '{}' to be a table of parts", day_key))?;
+            for (part_key, answer) in parts {
+                let part = parse_key(part_key, "part")?;
+                let answer = match answer {
+                    Value::Integer(n) => n.to_string(),
+                    Value::String(s) => s.clone(),
+                    _ => return Err(err!("Expected '{}.{}' to be a number or a string", day_key, part_key))
+                };
+                answers.insert((day,part), answer);
+            }
+        }
+        Ok(Expected { answers })
+    }
+}
+
+impl Expected {
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Expected,Error> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("Cannot read answers '{}': {}", path.display(), e)))?;
+        Expected::from_str(&input)
+            .map_err(|e| e.in_file(path.display().to_string()))
+    }
+
+    /// The answer expected for one part of a day, as it would be displayed.
+    pub fn get(&self, day: usize, part: usize) -> Option<&str> {
+        self.answers.get(&(day,part)).map(|s| s.as_str())
+    }
+
+    /// The parts of a day that we have answers for.
+    pub fn parts(&self, day: usize) -> impl Iterator<Item=usize> + '_ {
+        self.answers
+            .range((day,0)..(day+1,0))
+            .map(|(&(_,part),_)| part)
+    }
+
+}
+
+/// Keys look like "day12" or "part1"; return the number.
+fn parse_key(key: &str, prefix: &str) -> Result<usize,Error> {
+    key.strip_prefix(prefix)
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| err!("Expected a key like '{}1' but got '{}'", prefix, key))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn reads_answers() {
+        let expected = Expected::from_str(r#"
+            [day1]
+            part1 = 1234
+            part2 = "01234567"
+
+            [day8]
+            part2 = """
+            # #
+             #
+            """
+        "#).unwrap();
+        assert_eq!(expected.get(1, 1), Some("1234"));
+        assert_eq!(expected.get(1, 2), Some("01234567"));
+        assert_eq!(expected.get(8, 1), None);
+        assert_eq!(expected.get(8, 2), Some("            # #\n             #\n            "));
+        assert_eq!(expected.parts(1).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(expected.parts(2).count(), 0);
+    }
+
+    #[test]
+    fn rejects_bad_answers() {
+        assert!(Expected::from_str("[dayone]\npart1 = 1").is_err());
+        assert!(Expected::from_str("[day1]\nfirst = 1").is_err());
+        assert!(Expected::from_str("[day1]\npart1 = 1.5").is_err());
+        assert!(Expected::from_str("day1 = 1").is_err());
+    }
+
+}
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::ops::{ Add, Sub };
+
+/// A point on a 2D grid. Like the maps in the puzzles, `y`
+/// increases going down the page.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,PartialOrd,Ord,Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64
+}
+
+impl Point {
+
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    /// The point one step away in some direction.
+    pub fn step(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+
+    /// The 4 points directly touching this one, in the
+    /// order up, right, down, left.
+    pub fn neighbours(self) -> impl Iterator<Item=Point> {
+        Direction::ALL.iter().map(move |&d| self.step(d))
+    }
+
+    /// How far away another point is, moving only up, down, left and right.
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+}
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl From<(i64,i64)> for Point {
+    fn from((x,y): (i64,i64)) -> Point {
+        Point::new(x, y)
+    }
+}
+
+/// Which way something is facing or moving on a grid.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left
+}
+
+impl Direction {
+
+    /// Every direction, going clockwise from up.
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    /// The direction we'd face after turning 90° to the left.
+    pub fn left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up
+        }
+    }
+
+    /// The direction we'd face after turning 90° to the right.
+    pub fn right(self) -> Direction {
+        self.left().reverse()
+    }
+
+    /// The direction we'd face after turning around.
+    pub fn reverse(self) -> Direction {
+        self.left().left()
+    }
+
+    /// An arrow pointing this way, for drawing things that face some direction.
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<'
+        }
+    }
+
+    /// How far one step in this direction moves us.
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0)
+        }
+    }
+
+}
+
+/// The smallest rectangle containing some points; `min` is the top left
+/// corner and `max` the bottom right, and both are inside it.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point
+}
+
+impl BoundingBox {
+
+    /// The box around some points, or None if there aren't any.
+    pub fn around<I: IntoIterator<Item=Point>>(points: I) -> Option<BoundingBox> {
+        points.into_iter().fold(None, |bbox, p| Some(match bbox {
+            None => BoundingBox { min: p, max: p },
+            Some(BoundingBox { min, max }) => BoundingBox {
+                min: Point::new(min.x.min(p.x), min.y.min(p.y)),
+                max: Point::new(max.x.max(p.x), max.y.max(p.y))
+            }
+        }))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// Every point in the box, row by row from the top left.
+    pub fn points(self) -> impl Iterator<Item=Point> {
+        (self.min.y..=self.max.y).flat_map(move |y| (self.min.x..=self.max.x).map(move |x| Point::new(x, y)))
+    }
+
+    /// Draw the box a row at a time, asking for the character
+    /// to show at each point. Every row ends in a newline.
+    pub fn render<F: FnMut(Point) -> char>(&self, mut f: F) -> String {
+        let mut out = String::with_capacity((self.width() + 1) * self.height());
+        for y in self.min.y..=self.max.y {
+            for x in self.min.x..=self.max.x {
+                out.push(f(Point::new(x, y)));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+}
+
+/// A grid with something at every point from (0,0) up to (but not
+/// including) (width,height), stored row by row.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct DenseGrid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>
+}
+
+impl <T> DenseGrid<T> {
+
+    /// A grid filled with copies of some value.
+    pub fn new(width: usize, height: usize, fill: T) -> DenseGrid<T> where T: Clone {
+        DenseGrid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// A grid with whatever the function gives back at each point.
+    pub fn from_fn<F: FnMut(Point) -> T>(width: usize, height: usize, f: F) -> DenseGrid<T> {
+        let cells = (0..height as i64)
+            .flat_map(|y| (0..width as i64).map(move |x| Point::new(x, y)))
+            .map(f)
+            .collect();
+        DenseGrid { width, height, cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        if p.x < 0 || p.y < 0 || p.x as usize >= self.width || p.y as usize >= self.height {
+            None
+        } else {
+            Some(p.y as usize * self.width + p.x as usize)
+        }
+    }
+
+    /// What's at some point, or None if it's off the grid.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index(p).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index(p).map(move |idx| &mut self.cells[idx])
+    }
+
+    /// Put something at a point. Panics if the point is off the grid.
+    pub fn set(&mut self, p: Point, value: T) {
+        let idx = self.index(p).unwrap_or_else(|| panic!("{:?} is off the grid", p));
+        self.cells[idx] = value;
+    }
+
+    /// The box covering the whole grid.
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox { min: Point::ORIGIN, max: Point::new(self.width as i64 - 1, self.height as i64 - 1) }
+    }
+
+    /// Every point on the grid along with what's there, row by row.
+    pub fn iter(&self) -> impl Iterator<Item=(Point,&T)> {
+        self.bounding_box().points().zip(self.cells.iter())
+    }
+
+    /// The neighbours of a point that are on the grid.
+    pub fn neighbours(&self, p: Point) -> impl Iterator<Item=(Point,&T)> {
+        p.neighbours().filter_map(move |n| self.get(n).map(|t| (n, t)))
+    }
+
+    /// Draw the grid, asking for the character to show at each point.
+    pub fn render<F: FnMut(Point,&T) -> char>(&self, mut f: F) -> String {
+        self.bounding_box().render(|p| f(p, &self.cells[self.index(p).unwrap()]))
+    }
+
+}
+
+/// A grid with things at just some points, which can be anywhere.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point,T>
+}
+
+impl <T> Default for SparseGrid<T> {
+    fn default() -> SparseGrid<T> {
+        SparseGrid { cells: HashMap::new() }
+    }
+}
+
+impl <T> SparseGrid<T> {
+
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Every point that has something at it, in no particular order.
+    pub fn points(&self) -> impl Iterator<Item=Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(Point,&T)> {
+        self.cells.iter().map(|(&p, t)| (p, t))
+    }
+
+    /// The neighbours of a point that have something at them.
+    pub fn neighbours(&self, p: Point) -> impl Iterator<Item=(Point,&T)> {
+        p.neighbours().filter_map(move |n| self.get(n).map(|t| (n, t)))
+    }
+
+    /// The box around every point that has something at it.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::around(self.points())
+    }
+
+    /// Draw the part of the grid that has things in it, asking for
+    /// the character to show at each point (which may be empty).
+    pub fn render<F: FnMut(Point,Option<&T>) -> char>(&self, mut f: F) -> String {
+        match self.bounding_box() {
+            Some(bbox) => bbox.render(|p| f(p, self.get(p))),
+            None => String::new()
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn directions_turn() {
+        for &d in &Direction::ALL {
+            assert_eq!(d.left().right(), d);
+            assert_eq!(d.right().right(), d.reverse());
+            assert_eq!(Point::ORIGIN.step(d).step(d.reverse()), Point::ORIGIN);
+        }
+        assert_eq!(Direction::Up.right(), Direction::Right);
+        assert_eq!(Point::new(2, 3).neighbours().collect::<Vec<_>>(),
+            vec![Point::new(2, 2), Point::new(3, 3), Point::new(2, 4), Point::new(1, 3)]);
+    }
+
+    #[test]
+    fn grids_render() {
+        let mut sparse = SparseGrid::new();
+        sparse.insert(Point::new(-1, -1), '#');
+        sparse.insert(Point::new(1, 0), '#');
+        assert_eq!(sparse.bounding_box(), Some(BoundingBox { min: Point::new(-1, -1), max: Point::new(1, 0) }));
+        assert_eq!(sparse.render(|_, c| *c.unwrap_or(&'.')), "#..\n..#\n");
+
+        let dense = DenseGrid::from_fn(3, 2, |p| p.x == p.y);
+        assert_eq!(dense.get(Point::new(1, 1)), Some(&true));
+        assert_eq!(dense.get(Point::new(3, 0)), None);
+        assert_eq!(dense.neighbours(Point::ORIGIN).count(), 2);
+        assert_eq!(dense.render(|_, &b| if b { '#' } else { '.' }), "#..\n.#.\n");
+    }
+
+}
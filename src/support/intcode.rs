@@ -1,20 +1,52 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
 use crate::error::Error;
 use self::instruction::{ Instruction, VarType };
-use self::ops::Ops;
+pub use self::ops::Ops;
 
 pub fn parse_intcode_ops(input: &str) -> Result<Vec<i64>,Error> {
     let mut ns = vec![];
+    let mut column = 1;
     for (idx,s) in input.split(",").enumerate() {
         let n = s
             .trim()
             .parse()
-            .map_err(|_| err!("Cannot parse intcode string op {} ('{}') into an integer", idx+1, s))?;
-        ns.push(n)
+            .map_err(|_| Error::parse(1, column, format!("Cannot parse intcode string op {} ('{}') into an integer", idx+1, s.trim())))?;
+        ns.push(n);
+        column += s.chars().count() + 1;
     }
     Ok(ns)
 }
 
-#[derive(Clone)]
+/// A change to make to a program's memory before it runs, like day 2's
+/// "1202" patch or putting quarters into day 13's arcade cabinet. Written
+/// as ADDR=VALUE.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Poke {
+    pub addr: usize,
+    pub value: i64
+}
+
+impl Poke {
+    pub fn new(addr: usize, value: i64) -> Poke {
+        Poke { addr, value }
+    }
+}
+
+impl FromStr for Poke {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Poke,Error> {
+        let bad = || err!("Expected a poke like ADDR=VALUE (for example 0=2) but got '{}'", s);
+        let (addr, value) = s.split_once('=').ok_or_else(bad)?;
+        let addr = addr.trim().parse().map_err(|_| bad())?;
+        let value = value.trim().parse().map_err(|_| bad())?;
+        Ok(Poke { addr, value })
+    }
+}
+
+/// An Intcode interpreter. Step through the program with `step`, which
+/// stops each time the program needs input or has some output.
+#[derive(Debug,Clone)]
 pub struct Intcode {
     counter: usize,
     position: usize,
@@ -22,20 +54,44 @@ pub struct Intcode {
     ops: Ops
 }
 
-impl Intcode {
-    pub fn from_str(input: &str) -> Result<Intcode,Error> {
+impl FromStr for Intcode {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Intcode,Error> {
         let ops = parse_intcode_ops(input)?;
         Ok(Intcode::new(ops))
     }
+}
+
+impl Intcode {
     pub fn new(ops: Vec<i64>) -> Intcode {
-        Intcode { counter: 0, position: 0, relative_base: 0, ops: Ops::new(ops) }
+        Intcode::with_pokes(ops, &[])
+    }
+    /// A machine whose memory has been poked before it runs.
+    pub fn with_pokes(ops: Vec<i64>, pokes: &[Poke]) -> Intcode {
+        let mut ops = Ops::new(ops);
+        for poke in pokes {
+            ops.set(poke.addr, poke.value);
+        }
+        Intcode { counter: 0, position: 0, relative_base: 0, ops }
+    }
+    /// Change the program's memory, which can only be done before it starts.
+    pub fn poke(&mut self, poke: Poke) -> Result<(),Error> {
+        if self.counter > 0 || self.position > 0 {
+            return Err(Error::intcode("Memory can only be poked before the program starts"))
+        }
+        self.write(poke.addr, poke.value)?;
+        Ok(())
     }
     pub fn get_op(&self, pos: usize) -> i64 {
         self.ops.get(pos)
     }
+    /// The program's memory as it currently stands.
+    pub fn ops(&self) -> &Ops {
+        &self.ops
+    }
     pub fn provide_input(&mut self, input: ProvideInputValue) -> Result<(),Error> {
         if input.provider.counter != self.counter {
-            return Err(err!("Input provided to intcode machine twice"))
+            return Err(Error::intcode("Input provided to intcode machine twice"))
         }
         self.ops.set(input.provider.pos, input.value);
         self.set_position(self.position + 2);
@@ -48,25 +104,27 @@ impl Intcode {
     pub fn step(&mut self) -> Result<Option<Outcome>,Error> {
         loop {
             let pos = self.position;
-            let instr = Instruction::new(self.ops.get(pos) as usize);
+            let instr = Instruction::new(self.ops.get(pos))
+                .map_err(|e| Error::intcode(format!("{} at {}", e, pos)))?;
 
             match instr {
                 Instruction::Add(c,b,a) => {
-                    let c = self.get_value(c,1);
-                    let b = self.get_value(b,2);
-                    let a = self.get_pos(a,3);
-                    self.ops.set(a, b + c);
+                    let c = self.get_value(c,1)?;
+                    let b = self.get_value(b,2)?;
+                    let a = self.get_pos(a,3)?;
+                    self.write(a, b + c)?;
                     self.set_position(self.position + 4);
                 },
                 Instruction::Mul(c,b,a) => {
-                    let c = self.get_value(c,1);
-                    let b = self.get_value(b,2);
-                    let a = self.get_pos(a,3);
-                    self.ops.set(a, b * c);
+                    let c = self.get_value(c,1)?;
+                    let b = self.get_value(b,2)?;
+                    let a = self.get_pos(a,3)?;
+                    self.write(a, b * c)?;
                     self.set_position(self.position + 4);
                 },
                 Instruction::Input(c) => {
-                    let c = self.get_pos(c,1);
+                    let c = self.get_pos(c,1)?;
+                    check_address(c, pos)?;
                     // Computation is essentially suspended until
                     // this input provider is given input. If it's dropped
                     // without being given input, we'll be given another
@@ -77,44 +135,44 @@ impl Intcode {
                     })))
                 },
                 Instruction::Output(c) => {
-                    let c = self.get_value(c,1);
+                    let c = self.get_value(c,1)?;
                     self.set_position(self.position + 2);
                     break Ok(Some(Outcome::Output(c)))
                 },
                 Instruction::JumpIfTrue(c,b) => {
-                    let c = self.get_value(c,1);
+                    let c = self.get_value(c,1)?;
                     if c != 0 {
-                        let b = self.get_value(b,2);
-                        self.set_position(b as usize);
+                        let b = self.get_value(b,2)?;
+                        self.set_position(to_address(b, pos)?);
                     } else {
                         self.set_position(self.position + 3);
                     }
                 },
                 Instruction::JumpIfFalse(c,b) => {
-                    let c = self.get_value(c,1);
+                    let c = self.get_value(c,1)?;
                     if c == 0 {
-                        let b = self.get_value(b,2);
-                        self.set_position(b as usize);
+                        let b = self.get_value(b,2)?;
+                        self.set_position(to_address(b, pos)?);
                     } else {
                         self.set_position(self.position + 3);
                     }
                 },
                 Instruction::LessThan(c,b,a) => {
-                    let c = self.get_value(c,1);
-                    let b = self.get_value(b,2);
-                    let a = self.get_pos(a,3);
-                    self.ops.set(a, if c < b { 1 } else { 0 });
+                    let c = self.get_value(c,1)?;
+                    let b = self.get_value(b,2)?;
+                    let a = self.get_pos(a,3)?;
+                    self.write(a, if c < b { 1 } else { 0 })?;
                     self.set_position(self.position + 4);
                 },
                 Instruction::Equals(c,b,a) => {
-                    let c = self.get_value(c,1);
-                    let b = self.get_value(b,2);
-                    let a = self.get_pos(a,3);
-                    self.ops.set(a, if c == b { 1 } else { 0 });
+                    let c = self.get_value(c,1)?;
+                    let b = self.get_value(b,2)?;
+                    let a = self.get_pos(a,3)?;
+                    self.write(a, if c == b { 1 } else { 0 })?;
                     self.set_position(self.position + 4);
                 },
                 Instruction::AdjustRelativeBase(c) => {
-                    let c = self.get_value(c,1);
+                    let c = self.get_value(c,1)?;
                     self.relative_base += c;
                     self.set_position(self.position + 2);
                 }
@@ -124,23 +182,48 @@ impl Intcode {
             }
         }
     }
-    fn get_pos(&self, ty: VarType, offset: usize) -> usize {
+    fn get_pos(&self, ty: VarType, offset: usize) -> Result<usize,Error> {
         let position = self.position + offset;
         match ty {
-            VarType::Position => self.ops.get(position) as usize,
-            VarType::Immediate => position,
-            VarType::Relative => (self.ops.get(position) + self.relative_base) as usize
+            VarType::Position => to_address(self.ops.get(position), self.position),
+            VarType::Immediate => Ok(position),
+            VarType::Relative => to_address(self.ops.get(position) + self.relative_base, self.position)
         }
     }
-    fn get_value(&self, ty: VarType, offset: usize) -> i64 {
-        let pos = self.get_pos(ty, offset);
-        self.ops.get(pos)
+    fn get_value(&self, ty: VarType, offset: usize) -> Result<i64,Error> {
+        let pos = self.get_pos(ty, offset)?;
+        Ok(self.ops.get(pos))
+    }
+    fn write(&mut self, pos: usize, value: i64) -> Result<(),Error> {
+        check_address(pos, self.position)?;
+        self.ops.set(pos, value);
+        Ok(())
     }
 }
 
+/// The most memory a program can use. Writing past this is taken to be
+/// a mistake in the program, rather than growing memory to fit.
+const MAX_ADDRESS: usize = 1 << 24;
+
+/// A value used as an address by the instruction at `at`, which
+/// shouldn't be negative.
+fn to_address(value: i64, at: usize) -> Result<usize,Error> {
+    usize::try_from(value).map_err(|_| Error::intcode(format!("Negative address {} used at {}", value, at)))
+}
+
+/// Check that an address that's about to be written to by the
+/// instruction at `at` is within the memory a program can use.
+fn check_address(pos: usize, at: usize) -> Result<(),Error> {
+    if pos > MAX_ADDRESS {
+        return Err(Error::intcode(format!("Address {} used at {} is past the end of memory ({})", pos, at, MAX_ADDRESS)))
+    }
+    Ok(())
+}
+
 /// An outcome as a result of running a step of the Intcode
 /// interpreter. We stop because we either need input or
 /// have something to output.
+#[derive(Debug)]
 pub enum Outcome {
     NeedsInput(ProvideInput),
     Output(i64)
@@ -149,6 +232,7 @@ pub enum Outcome {
 /// This is handed back if the interpreter requires a value.
 /// Once given a value, it can be handed back to the interpreter
 /// to set the value. A value can only be provided exactly once.
+#[derive(Debug)]
 pub struct ProvideInput {
     counter: usize,
     pos: usize
@@ -161,6 +245,7 @@ impl ProvideInput {
 
 /// An input provider is turned into this when it's given a value.
 /// This can then be given back to the intcode machine to set the value.
+#[derive(Debug)]
 pub struct ProvideInputValue {
     provider: ProvideInput,
     value: i64
@@ -169,7 +254,9 @@ pub struct ProvideInputValue {
 /// Storage for ops that grows as necessary.
 mod ops {
 
-    #[derive(Clone)]
+    /// The memory of an Intcode machine. Reading past the end gives
+    /// back 0, and writing past the end grows it to fit.
+    #[derive(Debug,Clone,PartialEq,Eq)]
     pub struct Ops {
         ops: Vec<i64>
     }
@@ -187,6 +274,15 @@ mod ops {
             }
             self.ops[pos] = value;
         }
+        pub fn len(&self) -> usize {
+            self.ops.len()
+        }
+        pub fn is_empty(&self) -> bool {
+            self.ops.is_empty()
+        }
+        pub fn as_slice(&self) -> &[i64] {
+            &self.ops
+        }
     }
 
 }
@@ -210,22 +306,27 @@ mod instruction {
     }
 
     impl Instruction {
-        pub fn new(n: usize) -> Instruction {
+        /// Decode an instruction, or say what's wrong with it.
+        pub fn new(n: i64) -> Result<Instruction,String> {
+            if n < 0 {
+                return Err(format!("Unknown opcode {}", n))
+            }
             let op = n % 100;
-            let c  = VarType::new((n / 100) % 10);
-            let b  = VarType::new((n / 1000) % 10);
-            let a  = VarType::new((n / 10000) % 10);
+            let c  = VarType::new((n / 100) % 10, n)?;
+            let b  = VarType::new((n / 1000) % 10, n)?;
+            let a  = VarType::new((n / 10000) % 10, n)?;
             match op {
-                1 => Instruction::Add(c,b,a),
-                2 => Instruction::Mul(c,b,a),
-                3 => Instruction::Input(c),
-                4 => Instruction::Output(c),
-                5 => Instruction::JumpIfTrue(c,b),
-                6 => Instruction::JumpIfFalse(c,b),
-                7 => Instruction::LessThan(c,b,a),
-                8 => Instruction::Equals(c,b,a),
-                9 => Instruction::AdjustRelativeBase(c),
-                _ => Instruction::Finish
+                1 => Ok(Instruction::Add(c,b,a)),
+                2 => Ok(Instruction::Mul(c,b,a)),
+                3 => Ok(Instruction::Input(c)),
+                4 => Ok(Instruction::Output(c)),
+                5 => Ok(Instruction::JumpIfTrue(c,b)),
+                6 => Ok(Instruction::JumpIfFalse(c,b)),
+                7 => Ok(Instruction::LessThan(c,b,a)),
+                8 => Ok(Instruction::Equals(c,b,a)),
+                9 => Ok(Instruction::AdjustRelativeBase(c)),
+                99 => Ok(Instruction::Finish),
+                _ => Err(format!("Unknown opcode {}", n))
             }
         }
     }
@@ -238,13 +339,47 @@ mod instruction {
     }
 
     impl VarType {
-        fn new(n: usize) -> VarType {
+        fn new(n: i64, op: i64) -> Result<VarType,String> {
             match n {
-                0 => VarType::Position,
-                1 => VarType::Immediate,
-                _ => VarType::Relative
+                0 => Ok(VarType::Position),
+                1 => Ok(VarType::Immediate),
+                2 => Ok(VarType::Relative),
+                _ => Err(format!("Unknown parameter mode {} in opcode {}", n, op))
             }
         }
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn pokes_memory_before_running() {
+        assert_eq!("3=-7".parse::<Poke>().unwrap(), Poke::new(3, -7));
+        assert!("3".parse::<Poke>().is_err());
+        assert!("-1=2".parse::<Poke>().is_err());
+
+        // Add addresses 0 and 0, put the result in 0, and halt:
+        let mut intcode = Intcode::with_pokes(vec![1,0,0,0,99], &[Poke::new(0, 1)]);
+        intcode.poke(Poke::new(5, 3)).unwrap();
+        assert_eq!(intcode.ops().as_slice(), &[1,0,0,0,99,3]);
+        assert!(intcode.step().unwrap().is_none());
+        assert_eq!(intcode.get_op(0), 2);
+        assert!(intcode.poke(Poke::new(0, 1)).is_err());
+    }
+
+    #[test]
+    fn corrupt_programs_are_errors() {
+        let error = |program: &str| Intcode::from_str(program).unwrap().step().unwrap_err().to_string();
+        assert_eq!(error("1,0,0,0,42"), "Intcode error: Unknown opcode 42 at 4");
+        assert_eq!(error("-1"), "Intcode error: Unknown opcode -1 at 0");
+        assert_eq!(error("301,0,0,0"), "Intcode error: Unknown parameter mode 3 in opcode 301 at 0");
+        assert_eq!(error("1,-5,0,0"), "Intcode error: Negative address -5 used at 0");
+        assert_eq!(error("1105,1,-2"), "Intcode error: Negative address -2 used at 0");
+        assert_eq!(error("1101,1,1,1000000000000"), "Intcode error: Address 1000000000000 used at 0 is past the end of memory (16777216)");
+    }
+
+}
@@ -0,0 +1,118 @@
+use crate::error::Error;
+use crate::support::answer::Image;
+
+/// The block letters that puzzles draw their answers in, each 6 pixels
+/// high. Only the letters that have turned up in puzzles are known.
+const FONT: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"])
+];
+
+/// Read the letters drawn in an image. Letters are told apart by the
+/// blank columns between them; if any can't be read, the error says
+/// which, and shows the image so that it can be read by eye instead.
+pub fn read_letters(image: &Image) -> Result<String,Error> {
+    let rows: Vec<&[bool]> = image.rows()
+        .skip_while(|row| !row.contains(&true))
+        .collect();
+    let height = rows.iter().rposition(|row| row.contains(&true)).map(|n| n + 1).unwrap_or(0);
+    let rows = &rows[..height];
+    if height != 6 {
+        return Err(err!("Cannot read letters that are {} pixels high rather than 6:\n{}", height, image))
+    }
+
+    let width = rows[0].len();
+    let column_is_blank = |x: usize| rows.iter().all(|row| !row[x]);
+    let mut letters = String::new();
+    let mut unknown = vec![];
+    let mut x = 0;
+    while x < width {
+        if column_is_blank(x) {
+            x += 1;
+            continue
+        }
+        let start = x;
+        while x < width && !column_is_blank(x) {
+            x += 1;
+        }
+        let glyph: Vec<Vec<bool>> = rows.iter().map(|row| row[start..x].to_vec()).collect();
+        match FONT.iter().find(|(_, pattern)| glyph == trim_glyph(pattern)) {
+            Some(&(letter, _)) => letters.push(letter),
+            None => unknown.push(start.to_string())
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(letters)
+    } else {
+        Err(err!("Cannot read the letters starting at column {}:\n{}", unknown.join(", "), image))
+    }
+}
+
+/// A letter from the font as pixels, without the blank columns that some
+/// letters have at their edges (which can't be told apart from the gaps
+/// between letters).
+fn trim_glyph(pattern: &[&str; 6]) -> Vec<Vec<bool>> {
+    let pixels: Vec<Vec<bool>> = pattern.iter().map(|row| row.chars().map(|c| c == '#').collect()).collect();
+    let lit = |x: usize| pixels.iter().any(|row| row[x]);
+    let width = pixels[0].len();
+    let start = (0..width).find(|&x| lit(x)).unwrap_or(0);
+    let end = (0..width).rfind(|&x| lit(x)).map(|x| x + 1).unwrap_or(0);
+    pixels.iter().map(|row| row[start..end].to_vec()).collect()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn image(rows: &[&str]) -> Image {
+        let width = rows[0].len();
+        Image::from_fn(width, rows.len(), |x,y| rows[y].as_bytes()[x] == b'#')
+    }
+
+    #[test]
+    fn reads_letters() {
+        let hi = image(&[
+            "          ",
+            " #  # ### ",
+            " #  #  #  ",
+            " ####  #  ",
+            " #  #  #  ",
+            " #  #  #  ",
+            " #  # ### ",
+        ]);
+        assert_eq!(read_letters(&hi).unwrap(), "HI");
+    }
+
+    #[test]
+    fn says_which_letters_cannot_be_read() {
+        let unknown = image(&[
+            "#  # # #",
+            "#  # # #",
+            "####  # ",
+            "#  # # #",
+            "#  # # #",
+            "#  # # #",
+        ]);
+        let err = read_letters(&unknown).unwrap_err().to_string();
+        assert!(err.starts_with("Cannot read the letters starting at column 5:\n"), "{}", err);
+    }
+
+}
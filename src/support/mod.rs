@@ -1,11 +1,36 @@
 mod digits;
-mod uppercase;
 mod read_iter;
+mod write_string;
 pub mod intcode;
 pub mod terminal;
+pub mod console;
+pub mod transcript;
+pub mod script;
+pub mod answer;
+pub mod expected;
+pub mod description;
+pub mod examples;
+pub mod progress;
+pub mod grid;
+pub mod ascii_map;
+pub mod render;
+pub mod letters;
+pub mod animate;
+pub mod parse;
 
-pub use intcode::Intcode;
-pub use terminal::Terminal;
+pub use intcode::{ Intcode, Outcome, Ops };
+pub use terminal::{ Terminal, NonAscii };
+pub use console::Console;
+pub use script::Script;
+pub use answer::{ Answer, Image };
+pub use expected::Expected;
+pub use description::Description;
+pub use examples::{ Example, Examples };
+pub use progress::Progress;
+pub use grid::{ Point, Direction, BoundingBox, DenseGrid, SparseGrid };
+pub use ascii_map::{ AsciiMap, Whitespace };
+pub use render::Picture;
+pub use letters::read_letters;
 pub use digits::digits;
-pub use uppercase::Uppercase;
-pub use read_iter::{ ReadIter, IntoReadIter };
\ No newline at end of file
+pub use read_iter::{ ReadIter, IntoReadIter };
+pub use write_string::WriteString;
\ No newline at end of file
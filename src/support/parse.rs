@@ -0,0 +1,93 @@
+use std::str::FromStr;
+use crate::error::Error;
+
+/// A line of input with the whitespace around it trimmed off, which
+/// remembers where it came from so that errors can point into it.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Line<'a> {
+    /// Which line of the input this is, counting from 1.
+    pub number: usize,
+    /// The line, without whitespace at either end.
+    pub text: &'a str,
+    indent: usize
+}
+
+/// The lines of some input that aren't blank, trimmed so that
+/// inputs (and the examples in tests) can be indented.
+pub fn lines(input: &str) -> impl Iterator<Item=Line<'_>> {
+    input.lines().enumerate().filter_map(|(idx, line)| {
+        let text = line.trim();
+        if text.is_empty() {
+            return None
+        }
+        let indent = line[..line.len() - line.trim_start().len()].chars().count();
+        Some(Line { number: idx+1, text, indent })
+    })
+}
+
+/// The only line of some input, for puzzles whose input is all on one line.
+pub fn single_line(input: &str) -> Result<Line<'_>,Error> {
+    let mut lines = lines(input);
+    let line = lines.next().ok_or_else(|| Error::parse_somewhere("Expected a line of input but there isn't one"))?;
+    match lines.next() {
+        Some(extra) => Err(extra.error_at(extra.text, "Expected the input to be on one line")),
+        None => Ok(line)
+    }
+}
+
+impl <'a> Line<'a> {
+
+    /// The column that some part of the line starts at. Panics if
+    /// it's not a slice of `text`.
+    pub fn column_of(&self, part: &str) -> usize {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|&offset| offset + part.len() <= self.text.len())
+            .expect("should be given a part of the line");
+        self.indent + self.text[..offset].chars().count() + 1
+    }
+
+    /// An error pointing at some part of the line.
+    pub fn error_at<S: Into<String>>(&self, part: &str, message: S) -> Error {
+        Error::parse(self.number, self.column_of(part), message)
+    }
+
+    /// Parse some part of the line, saying what was expected if it can't be.
+    pub fn parse_at<T: FromStr>(&self, part: &str, expected: &str) -> Result<T,Error> {
+        part.parse().map_err(|_| self.error_at(part, format!("Expected {} but got '{}'", expected, part)))
+    }
+
+    /// The line as digits, each of which should be no bigger than `max`.
+    pub fn digits(&self, max: u8) -> Result<Vec<u8>,Error> {
+        self.text.char_indices().map(|(idx, c)| {
+            match c.to_digit(10) {
+                Some(d) if d <= max as u32 => Ok(d as u8),
+                _ => Err(self.error_at(&self.text[idx..], format!("Expected a digit from 0 to {} but got '{}'", max, c)))
+            }
+        }).collect()
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn lines_say_where_they_are() {
+        let input = "\n  12 ab\n\n\t3é x \n";
+        let lines: Vec<Line> = lines(input).collect();
+        assert_eq!(lines.iter().map(|l| (l.number, l.text)).collect::<Vec<_>>(), vec![(2, "12 ab"), (4, "3é x")]);
+        assert_eq!(lines[0].parse_at::<u8>(&lines[0].text[..2], "a number").unwrap(), 12);
+        assert_eq!(lines[0].parse_at::<u8>(&lines[0].text[3..], "a number").unwrap_err().to_string(),
+            "line 2, column 6: Expected a number but got 'ab'");
+        assert_eq!(lines[1].digits(9).unwrap_err().to_string(),
+            "line 4, column 3: Expected a digit from 0 to 9 but got 'é'");
+
+        assert_eq!(single_line(" 0120 \n").unwrap().digits(2).unwrap(), vec![0,1,2,0]);
+        assert_eq!(single_line("1\n2").unwrap_err().to_string(), "line 2, column 1: Expected the input to be on one line");
+        assert!(single_line("\n").is_err());
+    }
+
+}
@@ -0,0 +1,124 @@
+use std::io::Write;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::time::{ Duration, Instant };
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How often progress is redrawn.
+const REDRAW_EVERY: Duration = Duration::from_millis(200);
+
+/// Show progress on stderr from now on. Until this is called,
+/// progress is counted but never shown.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Reports how far along some long-running work is, on a single line of
+/// stderr that's redrawn every so often: how many things (states, steps..)
+/// have been worked through, how quickly, and if we know how many there
+/// will be, roughly how long is left. The line is finished off when the
+/// `Progress` is dropped.
+pub struct Progress {
+    label: String,
+    unit: &'static str,
+    total: Option<u64>,
+    count: u64,
+    enabled: bool,
+    started: Instant,
+    drawn: Option<Instant>
+}
+
+impl Progress {
+
+    /// Progress through some number of things that we can't know in advance.
+    pub fn new<S: Into<String>>(label: S, unit: &'static str) -> Progress {
+        Progress {
+            label: label.into(),
+            unit,
+            total: None,
+            count: 0,
+            enabled: ENABLED.load(Ordering::Relaxed),
+            started: Instant::now(),
+            drawn: None
+        }
+    }
+
+    /// Progress through at most `total` things, so that we can
+    /// estimate how long is left.
+    pub fn bounded<S: Into<String>>(label: S, unit: &'static str, total: u64) -> Progress {
+        let mut progress = Progress::new(label, unit);
+        progress.total = Some(total);
+        progress
+    }
+
+    /// One more thing has been worked through.
+    pub fn tick(&mut self) {
+        self.add(1);
+    }
+
+    /// Some more things have been worked through.
+    pub fn add(&mut self, n: u64) {
+        self.count += n;
+        if self.enabled {
+            let now = Instant::now();
+            let due = self.drawn.map(|t| now - t >= REDRAW_EVERY).unwrap_or(now - self.started >= REDRAW_EVERY);
+            if due {
+                self.draw(now);
+            }
+        }
+    }
+
+    /// How many things have been worked through so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    fn draw(&mut self, now: Instant) {
+        eprint!("\r{}\x1b[K", self.line(now - self.started));
+        let _ = std::io::stderr().flush();
+        self.drawn = Some(now);
+    }
+
+    fn line(&self, elapsed: Duration) -> String {
+        let secs = elapsed.as_secs_f64();
+        let rate = if secs > 0.0 { self.count as f64 / secs } else { 0.0 };
+        let mut line = format!("{}: {} {} ({:.0}/s)", self.label, self.count, self.unit, rate);
+        if let Some(total) = self.total {
+            line.push_str(&format!(" of at most {}", total));
+            if rate > 0.0 && total > self.count {
+                let eta = (total - self.count) as f64 / rate;
+                line.push_str(&format!(", at most {:.0}s to go", eta.ceil()));
+            }
+        }
+        line
+    }
+
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        // Only finish the line off if it was ever shown:
+        if self.drawn.is_some() {
+            let elapsed = self.started.elapsed();
+            eprintln!("\r{} in {:.1}s\x1b[K", self.line(elapsed), elapsed.as_secs_f64());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn describes_progress() {
+        let mut progress = Progress::bounded("day 14", "steps", 40);
+        progress.add(10);
+        assert_eq!(progress.count(), 10);
+        assert_eq!(progress.line(Duration::from_secs(2)), "day 14: 10 steps (5/s) of at most 40, at most 6s to go");
+
+        let progress = Progress::new("day 18", "states");
+        assert_eq!(progress.line(Duration::from_secs(1)), "day 18: 0 states (0/s)");
+    }
+
+}
@@ -11,11 +11,18 @@ impl <I: Iterator<Item=u8>> ReadIter<I> {
     }
 }
 
+impl ReadIter<std::vec::IntoIter<u8>> {
+    /// A Reader which hands back the bytes of the string given.
+    pub fn from_string<S: Into<String>>(s: S) -> ReadIter<std::vec::IntoIter<u8>> {
+        s.into().into_bytes().into_iter().into_reader()
+    }
+}
+
 impl <I: Iterator<Item=u8>> Read for ReadIter<I> {
     fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
-        for i in 0..buf.len() {
+        for (i, b) in buf.iter_mut().enumerate() {
             if let Some(byte) = self.iter.next() {
-                buf[0] = byte
+                *b = byte
             } else {
                 return Ok(i)
             }
@@ -34,4 +41,42 @@ impl <I: Iterator<Item=u8>> IntoReadIter for I {
     fn into_reader(self) -> ReadIter<Self::Reader> {
         ReadIter::new(self)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::io::{ BufRead, BufReader };
+
+    static TEXT: &str = "north\ntake antenna\nsouth\n";
+
+    #[test]
+    fn reads_into_any_size_of_buffer() {
+        for &size in &[1, 2, 3, 7, 64] {
+            let mut reader = TEXT.bytes().into_reader();
+            let mut buf = vec![0; size];
+            let mut out = vec![];
+            loop {
+                let n = reader.read(&mut buf).unwrap();
+                if n == 0 { break }
+                out.extend_from_slice(&buf[..n]);
+            }
+            assert_eq!(String::from_utf8(out).unwrap(), TEXT, "buffer size {}", size);
+        }
+    }
+
+    #[test]
+    fn reads_from_strings() {
+        let mut s = String::new();
+        ReadIter::from_string(TEXT).read_to_string(&mut s).unwrap();
+        assert_eq!(s, TEXT);
+
+        let lines: Vec<String> = BufReader::new(ReadIter::from_string(TEXT))
+            .lines()
+            .collect::<Result<_,_>>()
+            .unwrap();
+        assert_eq!(lines, vec!["north", "take antenna", "south"]);
+    }
+
+}
@@ -0,0 +1,212 @@
+use std::io;
+use std::path::{ Path, PathBuf };
+use std::cell::RefCell;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::io::Write;
+use crate::error::Error;
+use crate::support::answer::Image;
+use crate::support::grid::{ Point, BoundingBox };
+
+/// Shades to draw pictures with.
+pub const BLACK: u8 = 0;
+pub const DARK_GREY: u8 = 85;
+pub const LIGHT_GREY: u8 = 170;
+pub const WHITE: u8 = 255;
+
+thread_local! {
+    // Kept per thread so that days running side by side can't
+    // write to each other's files:
+    static TARGET: RefCell<Option<Target>> = const { RefCell::new(None) };
+}
+static HIDE_DRAWINGS: AtomicBool = AtomicBool::new(false);
+
+struct Target {
+    path: PathBuf,
+    format: Format,
+    scale: usize,
+    written: usize
+}
+
+/// The kinds of file that pictures can be written to, picked by extension.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+enum Format {
+    Pbm,
+    Pgm,
+    Png
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Result<Format,Error> {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("pbm") => Ok(Format::Pbm),
+            Some("pgm") => Ok(Format::Pgm),
+            Some("png") if cfg!(feature = "png") => Ok(Format::Png),
+            Some("png") => Err(err!("Writing PNG files needs the 'png' feature (cargo build --features png)")),
+            _ => Err(err!("Cannot render to '{}': the file should end in .pbm, .pgm or .png", path.display()))
+        }
+    }
+}
+
+/// Write pictures that days draw on this thread to a file from now on,
+/// with each pixel drawn `scale` pixels wide and high. Until this is
+/// called, pictures are ignored. If a day draws more than one picture, the second is
+/// written to `NAME-2.EXT`, the third to `NAME-3.EXT` and so on.
+pub fn render_to<P: AsRef<Path>>(path: P, scale: usize) -> Result<(),Error> {
+    let path = path.as_ref();
+    let format = Format::from_path(path)?;
+    if scale == 0 {
+        return Err(err!("The scale should be at least 1"))
+    }
+    TARGET.with(|t| *t.borrow_mut() = Some(Target { path: path.to_owned(), format, scale, written: 0 }));
+    Ok(())
+}
+
+/// Hand over a picture that's been drawn, to be written out
+/// if `render_to` has been called.
+pub fn render(picture: &Picture) -> Result<(),Error> {
+    TARGET.with(|target| match &mut *target.borrow_mut() {
+        Some(target) => write_picture(target, picture),
+        None => Ok(())
+    })
+}
+
+fn write_picture(target: &mut Target, picture: &Picture) -> Result<(),Error> {
+    target.written += 1;
+    let path = if target.written == 1 {
+        target.path.clone()
+    } else {
+        let stem = target.path.file_stem().unwrap_or_default().to_string_lossy();
+        let ext = target.path.extension().unwrap_or_default().to_string_lossy();
+        target.path.with_file_name(format!("{}-{}.{}", stem, target.written, ext))
+    };
+    let picture = picture.scaled(target.scale);
+    let bytes = match target.format {
+        Format::Pbm => picture.to_pbm(),
+        Format::Pgm => picture.to_pgm(),
+        Format::Png => picture.to_png()?
+    };
+    std::fs::write(&path, bytes)
+        .map_err(|e| io::Error::new(e.kind(), format!("Cannot write picture to '{}': {}", path.display(), e)))?;
+    Ok(())
+}
+
+/// Stop `show` writing anything from now on, for when days run
+/// side by side and what they draw would get jumbled up.
+pub fn hide_drawings() {
+    HIDE_DRAWINGS.store(true, Ordering::Relaxed);
+}
+
+/// Show something that a day has drawn as text (a map, say) on
+/// stderr, so that it doesn't get mixed up with the answers.
+pub fn show(drawn: &str) -> Result<(),Error> {
+    if !HIDE_DRAWINGS.load(Ordering::Relaxed) {
+        io::stderr().lock().write_all(drawn.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// A greyscale picture, with a shade from `BLACK` to `WHITE` for each pixel.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Picture {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>
+}
+
+impl Picture {
+
+    /// A picture that's `width` by `height`, with the shade that
+    /// the function hands back for each pixel.
+    pub fn from_fn<F: FnMut(usize,usize) -> u8>(width: usize, height: usize, mut f: F) -> Picture {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x,y)))
+            .map(|(x,y)| f(x,y))
+            .collect();
+        Picture { width, height, pixels }
+    }
+
+    /// A picture of the points in a box on some grid.
+    pub fn of_box<F: FnMut(Point) -> u8>(bbox: BoundingBox, mut f: F) -> Picture {
+        Picture::from_fn(bbox.width(), bbox.height(), |x,y| f(bbox.min + Point::new(x as i64, y as i64)))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The picture with each pixel made `scale` pixels wide and high.
+    pub fn scaled(&self, scale: usize) -> Picture {
+        Picture::from_fn(self.width * scale, self.height * scale, |x,y| {
+            self.pixels[(y / scale) * self.width + x / scale]
+        })
+    }
+
+    /// A plain PBM file, where each pixel is either black or white.
+    pub fn to_pbm(&self) -> Vec<u8> {
+        self.to_netpbm("P1", None, |shade| if shade < 128 { "1" } else { "0" }.to_owned())
+    }
+
+    /// A plain PGM file, keeping the shade of every pixel.
+    pub fn to_pgm(&self) -> Vec<u8> {
+        self.to_netpbm("P2", Some(WHITE), |shade| shade.to_string())
+    }
+
+    fn to_netpbm<F: Fn(u8) -> String>(&self, magic: &str, max: Option<u8>, pixel: F) -> Vec<u8> {
+        let mut out = format!("{}\n{} {}\n", magic, self.width, self.height);
+        if let Some(max) = max {
+            out.push_str(&format!("{}\n", max));
+        }
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let row: Vec<String> = row.iter().map(|&shade| pixel(shade)).collect();
+            out.push_str(&row.join(" "));
+            out.push('\n');
+        }
+        out.into_bytes()
+    }
+
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>,Error> {
+        let mut out = vec![];
+        let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| err!("Cannot encode PNG: {}", e))?;
+        writer.write_image_data(&self.pixels).map_err(|e| err!("Cannot encode PNG: {}", e))?;
+        writer.finish().map_err(|e| err!("Cannot encode PNG: {}", e))?;
+        Ok(out)
+    }
+
+    #[cfg(not(feature = "png"))]
+    pub fn to_png(&self) -> Result<Vec<u8>,Error> {
+        Err(err!("Writing PNG files needs the 'png' feature (cargo build --features png)"))
+    }
+
+}
+
+/// Lit pixels are drawn in black on white, like ink on a page.
+impl From<&Image> for Picture {
+    fn from(image: &Image) -> Picture {
+        let rows: Vec<&[bool]> = image.rows().collect();
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        Picture::from_fn(width, rows.len(), |x,y| if rows[y][x] { BLACK } else { WHITE })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn writes_netpbm() {
+        let picture = Picture::from(&Image::from_fn(3, 2, |x,y| x == y));
+        assert_eq!(String::from_utf8(picture.to_pbm()).unwrap(), "P1\n3 2\n1 0 0\n0 1 0\n");
+        let picture = Picture::from_fn(2, 1, |x,_| if x == 0 { DARK_GREY } else { WHITE }).scaled(2);
+        assert_eq!(String::from_utf8(picture.to_pgm()).unwrap(), "P2\n4 2\n255\n85 85 255 255\n85 85 255 255\n");
+    }
+
+}
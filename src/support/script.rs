@@ -0,0 +1,382 @@
+use std::cell::RefCell;
+use std::collections::{ HashMap, VecDeque };
+use std::io::{ self, Read, Write };
+use std::path::{ Path, PathBuf };
+use std::rc::Rc;
+use std::str::FromStr;
+use once_cell::sync::Lazy;
+use regex::{ Regex, Captures };
+use crate::error::Error;
+
+/// A script of input lines for an ASCII Intcode program. Each line of a
+/// script is sent as a line of input, except for:
+///
+/// - `# comments`, which run to the end of the line and are ignored.
+/// - `include FILE`, which includes the lines of another script (relative
+///   to the directory of the current one).
+/// - `let NAME = VALUE`, which sets a variable that can be used in any
+///   later line as `$NAME` or `${NAME}`.
+/// - `repeat N` ... `end`, which repeats the lines between N times.
+/// - `expect TEXT`, which fails the script unless TEXT appears in the
+///   output of the program before anything after it is sent.
+/// - `send TEXT`, which sends TEXT even if it looks like one of the above.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Script {
+    steps: Vec<Step>
+}
+
+/// A script is compiled down to a list of these.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum Step {
+    Send(String),
+    Expect(String)
+}
+
+/// Compile a script from a string. Any includes are relative
+/// to the current directory.
+impl FromStr for Script {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Script,Error> {
+        let mut compiler = Compiler::default();
+        compiler.compile(input, "<script>", Path::new(""))?;
+        Ok(Script { steps: compiler.steps })
+    }
+}
+
+impl Script {
+
+    /// Compile a script from the file given.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Script,Error> {
+        let mut compiler = Compiler::default();
+        compiler.compile_file(path.as_ref())?;
+        Ok(Script { steps: compiler.steps })
+    }
+
+    /// Turn the script into a Reader to feed to the program, and a Writer
+    /// for the program to write to (which passes the output on to the
+    /// writer given), so that `expect`s can be checked. Once the program
+    /// has finished, call `ScriptInput::finish` to check any `expect`s
+    /// that come after the last line of input.
+    pub fn into_io<W: Write>(self, writer: W) -> (ScriptInput, ScriptOutput<W>) {
+        let state = Rc::new(RefCell::new(State {
+            steps: self.steps.into_iter().collect(),
+            pending: VecDeque::new(),
+            output: Vec::new()
+        }));
+        (ScriptInput { state: state.clone() }, ScriptOutput { state, writer })
+    }
+
+}
+
+struct State {
+    steps: VecDeque<Step>,
+    pending: VecDeque<u8>,
+    // Output seen since the last `expect` was satisfied:
+    output: Vec<u8>
+}
+
+impl State {
+    /// Check that the output has what we expect in it, discarding
+    /// everything up to the end of the match so that the next `expect`
+    /// only looks at output after this one.
+    fn check_expected(&mut self, expected: &str) -> Result<(),Error> {
+        let needle = expected.as_bytes();
+        let found = self.output
+            .windows(needle.len().max(1))
+            .position(|w| w == needle);
+        match found {
+            Some(n) => {
+                self.output.drain(..n + needle.len());
+                Ok(())
+            },
+            None => {
+                Err(err!("Expected '{}' in the output but didn't see it", expected))
+            }
+        }
+    }
+}
+
+/// The Reader half of a running script; see `Script::into_io`.
+#[derive(Clone)]
+pub struct ScriptInput {
+    state: Rc<RefCell<State>>
+}
+
+impl ScriptInput {
+    /// Check any `expect`s left in the script against the output.
+    pub fn finish(&self) -> Result<(),Error> {
+        let mut state = self.state.borrow_mut();
+        while let Some(step) = state.steps.pop_front() {
+            match step {
+                Step::Expect(s) => state.check_expected(&s)?,
+                Step::Send(s) => return Err(err!("The program finished before '{}' was sent", s))
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Read for ScriptInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.state.borrow_mut();
+        while state.pending.is_empty() {
+            match state.steps.pop_front() {
+                Some(Step::Expect(s)) => {
+                    // This comes back out as the Error it is; see `From<io::Error>`:
+                    state.check_expected(&s).map_err(io::Error::other)?;
+                },
+                Some(Step::Send(s)) => {
+                    state.pending.extend(s.as_bytes());
+                    state.pending.push_back(b'\n');
+                },
+                None => {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "The script has no more input to give"))
+                }
+            }
+        }
+        let n = buf.len().min(state.pending.len());
+        for (b, p) in buf.iter_mut().zip(state.pending.drain(..n)) {
+            *b = p;
+        }
+        Ok(n)
+    }
+}
+
+/// The Writer half of a running script; see `Script::into_io`.
+pub struct ScriptOutput<W> {
+    state: Rc<RefCell<State>>,
+    writer: W
+}
+
+impl <W: Write> Write for ScriptOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.writer.write(buf)?;
+        self.state.borrow_mut().output.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// How deeply can scripts include other scripts? This mainly
+/// exists to catch scripts which include themselves.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+#[derive(Default)]
+struct Compiler {
+    variables: HashMap<String,String>,
+    steps: Vec<Step>,
+    depth: usize
+}
+
+impl Compiler {
+
+    fn compile_file(&mut self, path: &Path) -> Result<(),Error> {
+        if self.depth >= MAX_INCLUDE_DEPTH {
+            return Err(err!("Scripts are included too deeply (is '{}' including itself?)", path.display()))
+        }
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("Cannot read script '{}': {}", path.display(), e)))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        self.depth += 1;
+        let res = self.compile(&input, &path.display().to_string(), dir);
+        self.depth -= 1;
+        res
+    }
+
+    fn compile(&mut self, input: &str, name: &str, dir: &Path) -> Result<(),Error> {
+        let lines: Vec<(usize,&str)> = input
+            .lines()
+            .enumerate()
+            .map(|(idx,line)| (idx+1, strip_comment(line).trim()))
+            .filter(|(_,line)| !line.is_empty())
+            .collect();
+        self.compile_lines(&lines, name, dir)
+    }
+
+    fn compile_lines(&mut self, lines: &[(usize,&str)], name: &str, dir: &Path) -> Result<(),Error> {
+        let mut idx = 0;
+        while idx < lines.len() {
+            let (n, line) = lines[idx];
+            // Errors from included scripts know where they came from already:
+            let at = |e: Error| match e {
+                Error::Parse { position: Some(_), .. } => e,
+                e => Error::parse(n, 1, e.to_string()).in_file(name)
+            };
+            let (keyword, rest) = split_keyword(line);
+            match keyword {
+                "send" => {
+                    let s = self.substitute(rest).map_err(at)?;
+                    self.steps.push(Step::Send(s));
+                },
+                "expect" => {
+                    let s = self.substitute(rest).map_err(at)?;
+                    self.steps.push(Step::Expect(s));
+                },
+                "let" => {
+                    let eq = rest.find('=').ok_or_else(|| at(err!("Expected 'let NAME = VALUE'")))?;
+                    let var = rest[..eq].trim();
+                    if var.is_empty() || !var.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                        return Err(at(err!("'{}' is not a valid variable name", var)))
+                    }
+                    let value = self.substitute(rest[eq+1..].trim()).map_err(at)?;
+                    self.variables.insert(var.to_owned(), value);
+                },
+                "include" => {
+                    let file = self.substitute(rest).map_err(at)?;
+                    let path: PathBuf = dir.join(file);
+                    self.compile_file(&path).map_err(at)?;
+                },
+                "repeat" => {
+                    let count = self.substitute(rest).map_err(at)?;
+                    let count: usize = count
+                        .parse()
+                        .map_err(|_| at(err!("Cannot repeat '{}' times", count)))?;
+                    let end = find_end(lines, idx).ok_or_else(|| at(err!("'repeat' has no matching 'end'")))?;
+                    for _ in 0..count {
+                        self.compile_lines(&lines[idx+1..end], name, dir)?;
+                    }
+                    idx = end;
+                },
+                "end" => {
+                    return Err(at(err!("'end' without a matching 'repeat'")))
+                },
+                _ => {
+                    let s = self.substitute(line).map_err(at)?;
+                    self.steps.push(Step::Send(s));
+                }
+            }
+            idx += 1;
+        }
+        Ok(())
+    }
+
+    /// Replace any `$NAME` or `${NAME}` with the value of that variable.
+    fn substitute(&self, s: &str) -> Result<String,Error> {
+        static VAR: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$(?:\{(\w+)\}|(\w+))").unwrap());
+        let mut missing = None;
+        let out = VAR.replace_all(s, |cap: &Captures| {
+            let var = cap.get(1).or_else(|| cap.get(2)).unwrap().as_str();
+            match self.variables.get(var) {
+                Some(value) => value.clone(),
+                None => { missing = Some(var.to_owned()); String::new() }
+            }
+        });
+        match missing {
+            Some(var) => Err(err!("Variable '{}' is not set", var)),
+            None => Ok(out.into_owned())
+        }
+    }
+
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(n) => &line[..n],
+        None => line
+    }
+}
+
+/// Split a line into its first word (if it's a keyword) and the rest.
+fn split_keyword(line: &str) -> (&str, &str) {
+    let (first, rest) = match line.find(char::is_whitespace) {
+        Some(n) => (&line[..n], line[n..].trim()),
+        None => (line, "")
+    };
+    match first {
+        "send" | "expect" | "let" | "include" | "repeat" => (first, rest),
+        "end" if rest.is_empty() => (first, rest),
+        _ => ("", line)
+    }
+}
+
+/// Find the index of the `end` matching the `repeat` at `start`.
+fn find_end(lines: &[(usize,&str)], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, &(_, line)) in lines.iter().enumerate().skip(start) {
+        match split_keyword(line).0 {
+            "repeat" => depth += 1,
+            "end" => {
+                depth -= 1;
+                if depth == 0 { return Some(idx) }
+            },
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::support::{ Terminal, WriteString };
+
+    fn sends(steps: &[&str]) -> Vec<Step> {
+        steps.iter().map(|s| Step::Send(s.to_string())).collect()
+    }
+
+    #[test]
+    fn comments_variables_and_repeats() {
+        let script = Script::from_str("
+            # Go and get the thing:
+            let item = antenna
+            let times = 2
+            repeat $times
+                north # keep going
+                repeat 2
+                    west
+                end
+            end
+            take ${item}
+            send repeat
+        ").unwrap();
+        assert_eq!(script.steps, &*sends(&[
+            "north", "west", "west", "north", "west", "west", "take antenna", "repeat"
+        ]));
+    }
+
+    #[test]
+    fn bad_scripts() {
+        assert!(Script::from_str("repeat 2\nnorth").is_err());
+        assert!(Script::from_str("north\nend").is_err());
+        assert!(Script::from_str("take $item").is_err());
+        assert!(Script::from_str("repeat lots\nend").is_err());
+        assert!(Script::from_str("let = 2").is_err());
+    }
+
+    #[test]
+    fn includes() {
+        let dir = std::env::temp_dir().join(format!("aoc2019-script-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("inner.txt"), "let dir = south\n$dir\n").unwrap();
+        std::fs::write(dir.join("outer.txt"), "north\ninclude inner.txt\n$dir\n").unwrap();
+        std::fs::write(dir.join("loop.txt"), "include loop.txt\n").unwrap();
+
+        let script = Script::from_file(dir.join("outer.txt"));
+        let looped = Script::from_file(dir.join("loop.txt"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(script.unwrap().steps, &*sends(&["north", "south", "south"]));
+        assert!(looped.is_err());
+    }
+
+    #[test]
+    fn expectations() {
+        // Echo each character back until a 0 is read:
+        let program = "3,100,1006,100,11,4,100,1105,1,0,99,99";
+        let run = |script: &str| -> Result<String,Error> {
+            let script = Script::from_str(script)?;
+            let mut out = WriteString::new();
+            let (input, output) = script.into_io(&mut out);
+            Terminal::from_str(program, input.clone(), output)?.run()?;
+            input.finish()?;
+            Ok(out.into_string())
+        };
+        assert_eq!(run("hello\nexpect ell\nexpect o\nsend \0").unwrap(), "hello\n");
+        assert!(matches!(run("hello\nexpect ell\nexpect ell\nsend \0"), Err(Error::Message(_))));
+        assert!(matches!(run("hello\nsend \0\nexpect bye"), Err(Error::Message(_))));
+    }
+
+}
@@ -1,5 +1,7 @@
 use std::io::prelude::*;
+use std::str::FromStr;
 use crate::error::Error;
+use crate::support::transcript::Transcript;
 use crate::support::intcode::{
     Intcode,
     Outcome
@@ -11,7 +13,31 @@ use crate::support::intcode::{
 pub struct Terminal<R,W> {
     intcode: Intcode,
     reader: R,
-    writer: W
+    writer: W,
+    transcript: Option<Transcript>,
+    non_ascii: NonAscii,
+    values: Vec<i64>
+}
+
+/// What the terminal does when the program outputs a value that
+/// isn't ASCII. Whatever is chosen, every such value is kept, and
+/// can be seen with `Terminal::values`.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum NonAscii {
+    /// Hand the value back from `step` (the default).
+    Stop,
+    /// Keep running without handing the value back.
+    Collect,
+    /// Keep running, writing the value to the writer as `<n>`.
+    Inline
+}
+
+/// A terminal as it was at some point; see `Terminal::snapshot`.
+#[derive(Debug,Clone)]
+pub struct Snapshot {
+    intcode: Intcode,
+    values: usize,
+    entries: usize
 }
 
 impl <R: Read, W: Write> Terminal<R,W> {
@@ -22,36 +48,138 @@ impl <R: Read, W: Write> Terminal<R,W> {
     }
 
     pub fn new(intcode: Intcode, reader: R, writer: W) -> Terminal<R,W> {
-        Terminal { intcode, reader, writer }
+        Terminal { intcode, reader, writer, transcript: None, non_ascii: NonAscii::Stop, values: vec![] }
+    }
+
+    /// Decide what to do with non ASCII values (see `NonAscii`).
+    pub fn non_ascii(mut self, non_ascii: NonAscii) -> Terminal<R,W> {
+        self.non_ascii = non_ascii;
+        self
+    }
+
+    /// Every non ASCII value that the program has output so far.
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    /// Record everything read and written from now on as a transcript,
+    /// which is written to the writer provided as it happens.
+    pub fn record<T: Write + 'static>(&mut self, writer: T) {
+        self.transcript = Some(Transcript::new(writer));
+    }
+
+    /// The transcript being recorded, if any.
+    pub fn transcript(&self) -> Option<&Transcript> {
+        self.transcript.as_ref()
+    }
+
+    /// A copy of the machine as it currently stands, noting how much
+    /// has been output and recorded so far.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            intcode: self.intcode.clone(),
+            values: self.values.len(),
+            entries: self.transcript.as_ref().map(|t| t.entries().len()).unwrap_or(0)
+        }
+    }
+
+    /// Put the machine back to the state of some earlier snapshot,
+    /// forgetting any values and transcript entries since.
+    pub fn restore(&mut self, snapshot: Snapshot) -> Result<(),Error> {
+        self.intcode = snapshot.intcode;
+        self.values.truncate(snapshot.values);
+        if let Some(t) = &mut self.transcript { t.rollback(snapshot.entries)?; }
+        Ok(())
+    }
+
+    /// Runs the program until it finishes, handing back every non ASCII
+    /// value that it output along the way.
+    pub fn run(&mut self) -> Result<&[i64],Error> {
+        while self.step()?.is_some() {}
+        Ok(self.values())
     }
 
     /// Runs the Intcode interpreter until either there is an issue with
-    /// the Reader or Writer, a non ASCII value is handed back, or the
-    /// program finishes.
+    /// the Reader or Writer, a non ASCII value is handed back (if we're
+    /// set to stop on them), or the program finishes. If the Reader has no
+    /// input to give yet, it can return a `WouldBlock` error, and `step`
+    /// can be called again once it does.
     pub fn step(&mut self) -> Result<Option<i64>,Error> {
         loop {
             if let Some(outcome) = self.intcode.step()? {
                 match outcome {
                     Outcome::Output(c) => {
-                        if c >= 0 && c <= 127 {
+                        if (0..=127).contains(&c) {
                             // ASCII: push to the writer:
                             self.writer.write_all(&[c as u8][..])?;
+                            if let Some(t) = &mut self.transcript { t.output(c as u8)?; }
                         } else {
-                            // Non ASCII: output it:
-                            return Ok(Some(c))
+                            // Non ASCII: keep it, and do what we're told with it:
+                            if let Some(t) = &mut self.transcript { t.value(c)?; }
+                            self.values.push(c);
+                            match self.non_ascii {
+                                NonAscii::Stop => return Ok(Some(c)),
+                                NonAscii::Collect => {},
+                                NonAscii::Inline => write!(self.writer, "<{}>", c)?
+                            }
                         }
                     },
                     Outcome::NeedsInput(p) => {
                         // Pull ASCII from our reader:
+                        if let Some(t) = &mut self.transcript { t.end_output()?; }
                         let mut buf = [0;1];
                         self.reader.read_exact(&mut buf)?;
+                        if let Some(t) = &mut self.transcript { t.input(buf[0])?; }
                         self.intcode.provide_input(p.value(buf[0] as i64))?;
                     }
                 }
             } else {
+                if let Some(t) = &mut self.transcript { t.end_output()?; }
                 return Ok(None)
             }
         }
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::io::empty;
+
+    // Output 'a', 200, 'b' and then finish:
+    static PROGRAM: &str = "104,97,104,200,104,98,99";
+
+    fn run_with(non_ascii: NonAscii) -> (Vec<i64>, String) {
+        let mut out = vec![];
+        let values = {
+            let mut terminal = Terminal::from_str(PROGRAM, empty(), &mut out)
+                .unwrap()
+                .non_ascii(non_ascii);
+            terminal.run().unwrap().to_vec()
+        };
+        (values, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn stops_on_non_ascii() {
+        let mut out = vec![];
+        let mut terminal = Terminal::from_str(PROGRAM, empty(), &mut out).unwrap();
+        assert_eq!(terminal.step().unwrap(), Some(200));
+        assert_eq!(terminal.step().unwrap(), None);
+        assert_eq!(terminal.values(), &[200]);
+    }
+
+    #[test]
+    fn collects_non_ascii() {
+        assert_eq!(run_with(NonAscii::Stop), (vec![200], "ab".to_owned()));
+        assert_eq!(run_with(NonAscii::Collect), (vec![200], "ab".to_owned()));
+    }
+
+    #[test]
+    fn renders_non_ascii_inline() {
+        assert_eq!(run_with(NonAscii::Inline), (vec![200], "a<200>b".to_owned()));
+    }
+
+}
@@ -0,0 +1,250 @@
+use std::io::{ self, Write };
+use std::time::Instant;
+use crate::error::Error;
+use crate::support::{ Terminal, ReadIter };
+
+/// A single line of a transcript; something that was typed in, a line
+/// of ASCII that the program printed, or a non-ASCII value it handed back.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum Entry {
+    Input(String),
+    Output(String),
+    Value(i64)
+}
+
+/// Records everything passing through a `Terminal`, interleaving input
+/// and output line by line (with the time since recording started), so
+/// that a session can be replayed and checked later. Each line is written
+/// as soon as it's complete, so nothing is lost if the session is killed.
+pub struct Transcript {
+    started: Instant,
+    writer: Box<dyn Write>,
+    entries: Vec<Entry>,
+    pending_input: Vec<u8>,
+    pending_output: Vec<u8>
+}
+
+impl Transcript {
+
+    pub fn new<W: Write + 'static>(writer: W) -> Transcript {
+        Transcript {
+            started: Instant::now(),
+            writer: Box::new(writer),
+            entries: Vec::new(),
+            pending_input: Vec::new(),
+            pending_output: Vec::new()
+        }
+    }
+
+    /// Everything recorded so far (not including any incomplete line).
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Record a byte that was handed to the program.
+    pub fn input(&mut self, byte: u8) -> Result<(),Error> {
+        if byte == b'\n' {
+            let line = take_line(&mut self.pending_input);
+            self.push(Entry::Input(line))
+        } else {
+            self.pending_input.push(byte);
+            Ok(())
+        }
+    }
+
+    /// Record an ASCII byte that the program printed.
+    pub fn output(&mut self, byte: u8) -> Result<(),Error> {
+        if byte == b'\n' {
+            let line = take_line(&mut self.pending_output);
+            self.push(Entry::Output(line))
+        } else {
+            self.pending_output.push(byte);
+            Ok(())
+        }
+    }
+
+    /// Record a non-ASCII value that the program handed back.
+    pub fn value(&mut self, value: i64) -> Result<(),Error> {
+        self.end_output()?;
+        self.push(Entry::Value(value))
+    }
+
+    /// The program is waiting on input or has finished, so any partial
+    /// line of output (a prompt, say) is recorded as it stands.
+    pub fn end_output(&mut self) -> Result<(),Error> {
+        if self.pending_output.is_empty() {
+            return Ok(())
+        }
+        let line = take_line(&mut self.pending_output);
+        self.push(Entry::Output(line))
+    }
+
+    /// Go back to when there were `len` entries, forgetting any partial
+    /// lines too, as when a line of input is undone. What's been written
+    /// can't be taken back, so this is noted in the transcript instead,
+    /// as `^ LEN`, and `parse_transcript` drops the entries again.
+    pub fn rollback(&mut self, len: usize) -> Result<(),Error> {
+        self.pending_input.clear();
+        self.pending_output.clear();
+        self.entries.truncate(len);
+        self.write('^', &len.to_string())
+    }
+
+    fn push(&mut self, entry: Entry) -> Result<(),Error> {
+        match &entry {
+            Entry::Input(s) => self.write('>', s)?,
+            Entry::Output(s) => self.write('<', s)?,
+            Entry::Value(n) => self.write('=', &n.to_string())?
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    fn write(&mut self, kind: char, text: &str) -> Result<(),Error> {
+        let secs = self.started.elapsed().as_secs_f64();
+        writeln!(self.writer, "[{:>10.3}] {} {}", secs, kind, text)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+}
+
+fn take_line(bytes: &mut Vec<u8>) -> String {
+    let line = String::from_utf8_lossy(bytes).into_owned();
+    bytes.clear();
+    line
+}
+
+/// Parse a transcript written by `Transcript` back into its entries.
+pub fn parse_transcript(input: &str) -> Result<Vec<Entry>,Error> {
+    let mut entries = vec![];
+    for (idx,line) in input.lines().enumerate() {
+        if line.trim().is_empty() { continue }
+        let rest = line
+            .find("] ")
+            .map(|n| &line[n+2..])
+            .ok_or_else(|| Error::parse(idx+1, 1, "Expected a timestamp like '[1.234] '"))?;
+        let column = |part: &str| line[..line.len() - part.len()].chars().count() + 1;
+        let mut chars = rest.chars();
+        let kind = chars.next();
+        let text = chars.as_str();
+        let text = text.strip_prefix(' ').unwrap_or(text);
+        let entry = match kind {
+            Some('>') => Entry::Input(text.to_owned()),
+            Some('<') => Entry::Output(text.to_owned()),
+            Some('=') => Entry::Value(text.trim().parse()
+                .map_err(|_| Error::parse(idx+1, column(text), format!("Expected a number but got '{}'", text)))?),
+            Some('^') => {
+                // Some entries were undone; see `Transcript::rollback`:
+                let len = text.trim().parse().ok().filter(|&len| len <= entries.len())
+                    .ok_or_else(|| Error::parse(idx+1, column(text), format!("Expected a number of entries to go back to but got '{}'", text)))?;
+                entries.truncate(len);
+                continue
+            },
+            _ => return Err(Error::parse(idx+1, column(rest), "Expected '>' (input), '<' (output), '=' (a value) or '^' (an undo)"))
+        };
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Feed the inputs recorded in a transcript to a fresh machine running the
+/// program given, and check that it produces exactly the recorded output.
+/// The replay stops when the program finishes or the inputs run out.
+pub fn replay(program: &str, transcript: &str) -> Result<(),Error> {
+    let expected = parse_transcript(transcript)?;
+
+    let mut input = String::new();
+    for entry in &expected {
+        if let Entry::Input(s) = entry {
+            input.push_str(s);
+            input.push('\n');
+        }
+    }
+
+    let mut terminal = Terminal::from_str(program, ReadIter::from_string(input), io::sink())?;
+    terminal.record(io::sink());
+    loop {
+        match terminal.step() {
+            Ok(Some(_)) => continue,
+            Ok(None) => break,
+            Err(e) => {
+                if e.io_kind() == Some(io::ErrorKind::UnexpectedEof) { break } else { return Err(e) }
+            }
+        }
+    }
+
+    let actual = terminal.transcript().map(|t| t.entries()).unwrap_or(&[]);
+    for (idx,(e,a)) in expected.iter().zip(actual).enumerate() {
+        if e != a {
+            return Err(err!("Transcript differs at entry {}: expected {:?} but got {:?}", idx+1, e, a))
+        }
+    }
+    if expected.len() != actual.len() {
+        return Err(err!("Transcript has {} entries but the replay produced {}", expected.len(), actual.len()))
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::support::IntoReadIter;
+    use std::rc::Rc;
+    use std::cell::RefCell;
+
+    // Echo each character back, forever:
+    static ECHO: &str = "3,100,4,100,1105,1,0";
+
+    #[derive(Clone,Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn record(input: &str) -> String {
+        let out = Shared::default();
+        let reader = input.as_bytes().to_vec().into_iter().into_reader();
+        let mut terminal = Terminal::from_str(ECHO, reader, io::sink()).unwrap();
+        terminal.record(out.clone());
+        assert!(terminal.step().is_err(), "echo should run until input runs out");
+        let bytes = out.0.borrow().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn records_and_replays() {
+        let transcript = record("hello\nworld\n");
+        let entries = parse_transcript(&transcript).unwrap();
+        assert_eq!(entries, vec![
+            Entry::Output("h".to_owned()),
+            Entry::Output("e".to_owned()),
+            Entry::Output("l".to_owned()),
+            Entry::Output("l".to_owned()),
+            Entry::Output("o".to_owned()),
+            Entry::Input("hello".to_owned()),
+            Entry::Output("".to_owned()),
+            Entry::Output("w".to_owned()),
+            Entry::Output("o".to_owned()),
+            Entry::Output("r".to_owned()),
+            Entry::Output("l".to_owned()),
+            Entry::Output("d".to_owned()),
+            Entry::Input("world".to_owned()),
+            Entry::Output("".to_owned()),
+        ]);
+        replay(ECHO, &transcript).unwrap();
+    }
+
+    #[test]
+    fn replay_detects_differences() {
+        let transcript = record("hi\n").replace("< i", "< x");
+        assert!(replay(ECHO, &transcript).is_err());
+    }
+
+}
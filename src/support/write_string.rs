@@ -0,0 +1,61 @@
+use std::io::{ Write, Result as IoResult };
+
+/// A Writer which collects everything written to it, so that the
+/// output can be looked at as a String afterwards. Pass `&mut` one of
+/// these to things that take ownership of a Writer.
+#[derive(Debug,Clone,Default)]
+pub struct WriteString {
+    bytes: Vec<u8>
+}
+
+impl WriteString {
+    pub fn new() -> WriteString {
+        WriteString { bytes: Vec::new() }
+    }
+    /// Everything written, as a String. Anything that isn't valid
+    /// UTF-8 is replaced with U+FFFD.
+    pub fn into_string(self) -> String {
+        match String::from_utf8(self.bytes) {
+            Ok(s) => s,
+            Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned()
+        }
+    }
+}
+
+impl Write for WriteString {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::support::{ Terminal, ReadIter };
+
+    #[test]
+    fn collects_writes() {
+        let mut out = WriteString::new();
+        write!(out, "Hello {}", 2019).unwrap();
+        out.write_all(b"!\n").unwrap();
+        assert_eq!(out.into_string(), "Hello 2019!\n");
+    }
+
+    #[test]
+    fn captures_terminal_output() {
+        // Echo each character back until a 0 is read:
+        let program = "3,100,1006,100,11,4,100,1105,1,0,99,99";
+        let mut out = WriteString::new();
+        Terminal::from_str(program, ReadIter::from_string("hi there\n\0"), &mut out)
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(out.into_string(), "hi there\n");
+    }
+
+}
@@ -0,0 +1,78 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{ Command, Stdio };
+use serde_json::Value;
+use advent_of_code_2019::support::{ Example, Examples };
+
+/// Parts that take too long without optimisations to check by default,
+/// like the slow days in the verify tests.
+const SLOW_PARTS: &[(usize,usize)] = &[(16, 2)];
+
+/// Run a day on an example's input, handing back the answer it gives.
+fn run(day: usize, example: &Example) -> Result<String,String> {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_aoc2019"));
+    command.arg(format!("day{}", day));
+    // Days that don't read an input (like day 4) have examples without one:
+    if !example.input.is_empty() {
+        command.arg("-");
+    }
+    let mut child = command
+        .args(["--format", "json", "--part", &example.part.to_string()])
+        .args(&example.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("aoc2019 should run");
+    child.stdin.take().unwrap().write_all(example.input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+    let record: Value = serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    match &record["answer"] {
+        Value::String(s) => Ok(s.clone()),
+        // Pictures come back as a list of rows:
+        Value::Array(rows) => Ok(rows.iter().map(|row| row.as_str().unwrap_or_default()).collect::<Vec<_>>().join("\n")),
+        answer => Ok(answer.to_string())
+    }
+}
+
+/// Run the examples kept in the examples folder (see `aoc2019 examples`)
+/// for the parts picked out, failing if any don't give the answer that
+/// their description says they should.
+fn check_examples<F: Fn(usize,usize) -> bool>(wanted: F) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let mut paths: Vec<_> = std::fs::read_dir(&dir)
+        .expect("examples folder should exist")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map(|e| e == "toml").unwrap_or(false))
+        .collect();
+    paths.sort();
+
+    let mut failures = vec![];
+    for path in paths {
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let day: usize = name.trim_start_matches("day").parse().expect("examples should be named dayNN.toml");
+        let examples = Examples::from_file(&path).unwrap();
+        for (idx, example) in examples.0.iter().enumerate().filter(|(_,e)| wanted(day, e.part)) {
+            match run(day, example) {
+                Ok(answer) if answer == example.answer => {},
+                Ok(answer) => failures.push(format!("{} example {}: expected {} but got {}", name, idx+1, example.answer, answer)),
+                Err(e) => failures.push(format!("{} example {}: failed: {}", name, idx+1, e))
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn examples_give_the_stated_answers() {
+    check_examples(|day, part| !SLOW_PARTS.contains(&(day, part)));
+}
+
+#[test]
+#[ignore]
+fn slow_examples_give_the_stated_answers() {
+    check_examples(|day, part| SLOW_PARTS.contains(&(day, part)));
+}
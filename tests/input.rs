@@ -0,0 +1,27 @@
+use std::io::Write;
+use std::process::{ Command, Stdio };
+
+#[test]
+fn input_can_be_piped_in() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aoc2019"))
+        .args(["day1", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("aoc2019 should run");
+    child.stdin.take().unwrap().write_all(b"12\n1969\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Star 1: 656\nStar 2: 968\n");
+}
+
+#[test]
+fn input_is_found_in_the_input_folder() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2019"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["day1", "--input-dir", "no-such-folder"])
+        .output()
+        .expect("aoc2019 should run");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no-such-folder/day01.txt"));
+}
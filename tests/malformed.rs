@@ -0,0 +1,54 @@
+use std::io::Write;
+use std::process::{ Command, Stdio };
+
+/// Run a day with some input piped in, handing back the exit code and
+/// what was written to stderr.
+fn run_with_input(args: &[&str], input: &str) -> (Option<i32>, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aoc2019"))
+        .args(args)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("aoc2019 should run");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    (output.status.code(), String::from_utf8_lossy(&output.stderr).trim_end().to_owned())
+}
+
+#[test]
+fn malformed_inputs_say_where_they_go_wrong() {
+    let cases: &[(&[&str], &str, &str)] = &[
+        (&["day1"], "12\n  1x9\n", "<stdin>:2:3: Expected a mass but got '1x9'"),
+        (&["day2"], "1,0,x,3,99", "<stdin>:1:5: Cannot parse intcode string op 3 ('x') into an integer"),
+        (&["day3"], "R8,U5\nR8,X3\n", "<stdin>:2:4: Expected a direction (U, D, L or R) but got 'X3'"),
+        (&["day6"], "COM)B\nB)C\nCOM)C\n", "<stdin>:3:5: 'C' orbits more than one object"),
+        (&["day6"], "COM)B\nB-C\n", "<stdin>:2:1: Expected a line like 'A)B' but got 'B-C'"),
+        (&["day6", "--part", "2"], "COM)B\nB)YOU\n", "<stdin>: Expected SAN to be orbiting something but it isn't in the map"),
+        (&["day8", "--image-width", "3", "--image-height", "1"], "0120", "<stdin>:1:4: Expected whole 3x1 layers but the last is only 1 of 3 pixels"),
+        (&["day8", "--part", "2", "--image-width", "2", "--image-height", "1"], "0130", "<stdin>:1:3: Expected a digit from 0 to 2 but got '3'"),
+        (&["day10"], ".#\n#?\n", "<stdin>:2:2: Unexpected character '?' in map"),
+        (&["day10"], "...\n...\n", "<stdin>: Expected some asteroids ('#') in the map but there aren't any"),
+        (&["day12"], "<x=1, y=2, z=3>\n<x=1, y=two, z=3>\n", "<stdin>:2:9: Expected a number but got 'two'"),
+        (&["day12"], "<x=1, y=2>\n", "<stdin>:1:1: Expected a moon like '<x=1, y=2, z=3>' but got '<x=1, y=2>'"),
+        (&["day14"], "10 ORE => 1 A\n1 A, 2 B => 1 FUEL\n", "<stdin>:2:8: No reaction makes B"),
+        (&["day14"], "10 ORE => 1 A\n1 A -> 1 FUEL\n", "<stdin>:2:1: Expected a reaction like '7 A, 1 B => 1 C' but got '1 A -> 1 FUEL'"),
+        (&["day14"], "10 ORE => 1 A\n1 A, 1 D => 1 C\n1 C => 1 D\n1 D => 1 FUEL\n", "<stdin>:3:3: Making D needs C, which can't be made without D"),
+        (&["day14"], "10 ORE => 0 FUEL\n", "<stdin>:1:11: Expected an amount of at least 1"),
+        (&["day16"], "12345a78\n", "<stdin>:1:6: Expected a digit from 0 to 9 but got 'a'"),
+        (&["day16"], "123\n456\n", "<stdin>:2:1: Expected the input to be on one line"),
+        (&["day18"], "#a.\n", "<stdin>: Expected an '@' to start from in the map but there isn't one"),
+        (&["day20"], "  A\n #.#\n", "<stdin>:1:3: Expected the portal label 'A' to have two letters"),
+        (&["day20"], "#.#\n", "<stdin>: Expected an AA portal to start from but there isn't one"),
+        (&["day22"], "cut 3\ndeal with increment 0\n", "<stdin>:2:21: Expected an increment of at least 1"),
+        (&["day22"], "cut three\n", "<stdin>:1:5: Expected a number of cards to cut but got 'three'"),
+        (&["day22"], "shuffle\n", "<stdin>:1:1: Expected a technique like 'deal into new stack', 'cut N' or 'deal with increment N' but got 'shuffle'"),
+        (&["day24"], "#....\n.....\n", "<stdin>: Expected a 5x5 map of bugs but it's 5x2"),
+    ];
+    for (args, input, error) in cases {
+        let (code, stderr) = run_with_input(args, input);
+        assert_eq!(code, Some(3), "{:?} should fail to parse '{}'", args, input);
+        assert_eq!(&stderr, error, "{:?} given '{}'", args, input);
+    }
+}
@@ -0,0 +1,34 @@
+use std::process::Command;
+
+/// These days take minutes to run without optimisations, so they're
+/// only checked when ignored tests are asked for (ideally along with
+/// `--release`).
+const SLOW_DAYS: &[usize] = &[16, 18];
+
+/// Run `aoc2019 verify` on some days, failing with its output if any
+/// of the answers differ from those in answers.toml.
+fn verify(days: impl Iterator<Item=usize>) {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2019"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .arg("verify")
+        .args(days.map(|n| n.to_string()))
+        .output()
+        .expect("aoc2019 should run");
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn answers_are_correct() {
+    verify((1..=25).filter(|n| !SLOW_DAYS.contains(n)));
+}
+
+#[test]
+#[ignore]
+fn slow_answers_are_correct() {
+    verify(SLOW_DAYS.iter().copied());
+}